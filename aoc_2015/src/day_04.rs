@@ -1,8 +1,6 @@
+use common::md5::Md5Search;
 #[allow(unused_imports)]
-use helper::{
-    print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner, MD5,
-};
-use std::fmt::Write;
+use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
 #[derive(Default)]
 pub struct Day04 {
@@ -16,18 +14,16 @@ impl Day04 {
 
     pub fn scan<F>(&mut self, f: F) -> Result<usize, Error>
     where
-        F: Fn(u8) -> bool,
+        F: Fn(u8) -> bool + Sync,
     {
-        let len = self.key.len();
-        for i in 0.. {
-            write!(self.key, "{i}")?;
-            let digest = MD5::digest(self.key.as_bytes());
-            self.key.truncate(len);
-            if digest[0] == 0 && digest[1] == 0 && f(digest[2]) {
-                return Ok(i);
-            }
+        let search = Md5Search::new(&self.key)?;
+        let mut matches = search.find(0, |digest| {
+            (digest[0] == 0 && digest[1] == 0 && f(digest[2])).then_some(())
+        });
+        match matches.next() {
+            Some(found) => Ok(found?.0),
+            None => Err(Error::Unsolved),
         }
-        unreachable!();
     }
 }
 
@@ -35,7 +31,7 @@ impl Runner for Day04 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
         assert_eq!(lines.len(), 1);
-        self.key.write_str(lines[0].as_str())?;
+        self.key = lines[0].clone();
         Ok(())
     }
 
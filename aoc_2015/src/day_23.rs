@@ -1,7 +1,8 @@
+use asm::{Args, Effect, InstructionSet, Machine};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
-use std::str::FromStr;
 
+#[derive(Copy, Clone)]
 enum Op {
     Hlf(usize),
     Tpl(usize),
@@ -11,53 +12,46 @@ enum Op {
     Jio(usize, isize),
 }
 
-impl FromStr for Op {
-    type Err = Error;
+impl InstructionSet for Op {
+    const REGISTERS: &'static [&'static str] = &["a", "b"];
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn register(c: char) -> usize {
-            (c as u8 - b'a') as usize
+    fn parse(op: &str, args: &Args) -> Result<Self, Error> {
+        match op {
+            "hlf" => Ok(Self::Hlf(args.reg(0)?)),
+            "tpl" => Ok(Self::Tpl(args.reg(0)?)),
+            "inc" => Ok(Self::Inc(args.reg(0)?)),
+            "jmp" => Ok(Self::Jmp(args.imm(0)?)),
+            "jie" => Ok(Self::Jie(args.reg(0)?, args.imm(1)?)),
+            "jio" => Ok(Self::Jio(args.reg(0)?, args.imm(1)?)),
+            _ => Err(args.invalid()),
         }
+    }
 
-        match &s[0..3] {
-            "hlf" => Ok(Self::Hlf(register(s.chars().nth(4).unwrap()))),
-            "tpl" => Ok(Self::Tpl(register(s.chars().nth(4).unwrap()))),
-            "inc" => Ok(Self::Inc(register(s.chars().nth(4).unwrap()))),
-            "jmp" => Ok(Self::Jmp(s[4..].parse()?)),
-            "jie" => {
-                if &s[7..8] == "+" {
-                    Ok(Self::Jie(
-                        register(s.chars().nth(4).unwrap()),
-                        s[8..].parse()?,
-                    ))
-                } else {
-                    Ok(Self::Jie(
-                        register(s.chars().nth(4).unwrap()),
-                        s[7..].parse()?,
-                    ))
+    fn execute(self, machine: &mut Machine<Self>) -> Effect {
+        let registers = &mut machine.registers;
+        match self {
+            Self::Hlf(r) => registers[r] /= 2,
+            Self::Tpl(r) => registers[r] *= 3,
+            Self::Inc(r) => registers[r] += 1,
+            Self::Jmp(offset) => return Effect::Jump(offset),
+            Self::Jie(r, offset) => {
+                if registers[r] % 2 == 0 {
+                    return Effect::Jump(offset);
                 }
             }
-            "jio" => {
-                if &s[7..8] == "+" {
-                    Ok(Self::Jio(
-                        register(s.chars().nth(4).unwrap()),
-                        s[8..].parse()?,
-                    ))
-                } else {
-                    Ok(Self::Jio(
-                        register(s.chars().nth(4).unwrap()),
-                        s[7..].parse()?,
-                    ))
+            Self::Jio(r, offset) => {
+                if registers[r] == 1 {
+                    return Effect::Jump(offset);
                 }
             }
-            _ => Err(Error::InvalidInput(s.to_string())),
         }
+        Effect::Next
     }
 }
 
 #[derive(Default)]
 pub struct Day23 {
-    ops: Vec<Op>,
+    machine: Machine<Op>,
 }
 
 impl Day23 {
@@ -65,40 +59,18 @@ impl Day23 {
         Self::default()
     }
 
-    fn run(&self, a: usize) -> usize {
-        let mut registers = [a, 0usize];
-        let mut pc = 0isize;
-        while let Some(op) = self.ops.get(pc as usize) {
-            let mut next_pc = pc + 1;
-            match op {
-                Op::Hlf(r) => registers[*r] /= 2,
-                Op::Tpl(r) => registers[*r] *= 3,
-                Op::Inc(r) => registers[*r] += 1,
-                Op::Jmp(offset) => next_pc = pc + offset,
-                Op::Jie(r, offset) => {
-                    if registers[*r] % 2 == 0 {
-                        next_pc = pc + offset;
-                    }
-                }
-                Op::Jio(r, offset) => {
-                    if registers[*r] == 1 {
-                        next_pc = pc + offset;
-                    }
-                }
-            }
-            pc = next_pc;
-        }
-
-        registers[1]
+    fn run(&mut self, a: isize) -> isize {
+        self.machine.reset();
+        self.machine.registers[0] = a;
+        self.machine.run();
+        self.machine.registers[1]
     }
 }
 
 impl Runner for Day23 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for line in lines.iter() {
-            self.ops.push(line.parse()?);
-        }
+        self.machine = Machine::parse(lines.iter())?;
         Ok(())
     }
 
@@ -0,0 +1 @@
+common::sample_tests!(aoc_2015::register, 2015);
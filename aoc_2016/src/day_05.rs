@@ -1,8 +1,6 @@
+use common::md5::Md5Search;
 #[allow(unused_imports)]
-use helper::{
-    print, println, Error, HashMap, HashSet, Lines, LinesOpt, MD5String, Output, RunOutput, Runner,
-    MD5,
-};
+use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
 #[derive(Default)]
 pub struct Day05 {
@@ -15,31 +13,6 @@ impl Day05 {
     }
 }
 
-struct NumberString {
-    bytes: Vec<u8>,
-}
-
-impl Default for NumberString {
-    fn default() -> Self {
-        Self { bytes: vec![b'0'] }
-    }
-}
-
-impl NumberString {
-    fn inc(&mut self) {
-        for c in self.bytes.iter_mut().rev() {
-            if *c == b'9' {
-                *c = b'0';
-            } else {
-                *c += 1;
-                return;
-            }
-        }
-        self.bytes[0] = b'1';
-        self.bytes.push(b'0');
-    }
-}
-
 impl Runner for Day05 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
@@ -57,61 +30,52 @@ impl Runner for Day05 {
     }
 }
 
+fn hex_digit(v: u8) -> char {
+    if v < 10 {
+        (v + b'0') as char
+    } else {
+        ((v - 10) + b'a') as char
+    }
+}
+
 impl Day05 {
+    // Digests starting with five zeros, giving the sixth and seventh hex digits
+    fn interesting(&self) -> Result<impl Iterator<Item = Result<(u8, u8), Error>>, Error> {
+        let search = Md5Search::new(&self.salt)?;
+        Ok(search
+            .find(0, |digest| {
+                (digest[0] == 0 && digest[1] == 0 && digest[2] & 0xf0 == 0)
+                    .then_some((digest[2] & 0xf, digest[3] >> 4))
+            })
+            .map(|found| found.map(|(_, digits)| digits)))
+    }
+
     fn part1(&mut self) -> Result<RunOutput, Error> {
         let mut password = String::with_capacity(8);
-        let mut hash: MD5String = self.salt.parse()?;
-        let mut num = NumberString::default();
-        loop {
-            num.inc();
-            hash.truncate_without_zero(self.salt.len());
-            hash.push_bytes(&num.bytes)?;
-            let digest = hash.digest(); // MD5::digest(hash.as_bytes());
-            if digest[0] == 0 && digest[1] == 0 && digest[2] & 0xF0 == 0 {
-                let v = digest[2] & 0xf;
-                password.push(if v < 10 {
-                    (v + b'0') as char
-                } else {
-                    ((v - 10) + b'a') as char
-                });
-                if password.len() == 8 {
-                    break;
-                }
+        for found in self.interesting()? {
+            let (v, _) = found?;
+            password.push(hex_digit(v));
+            if password.len() == 8 {
+                return Ok(password.into());
             }
         }
-        Ok(password.into())
+        Err(Error::Unsolved)
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
         let mut password = [None; 8];
         let mut found = 0;
-        let mut hash: MD5String = self.salt.parse()?;
-        let mut num = NumberString::default();
-        loop {
-            num.inc();
-            hash.truncate_without_zero(self.salt.len());
-            hash.push_bytes(&num.bytes)?;
-            let digest = hash.digest(); // MD5::digest(hash.as_bytes());
-            if digest[0] == 0 && digest[1] == 0 && digest[2] & 0xF0 == 0 {
-                let idx = (digest[2] & 0xf) as usize;
-                let v = (digest[3] & 0xf0) >> 4;
-                if idx < 8 && password[idx].is_none() {
-                    password[idx] = Some(if v < 10 {
-                        (v + b'0') as char
-                    } else {
-                        ((v - 10) + b'a') as char
-                    });
-                    found += 1;
-                    if found == 8 {
-                        break;
-                    }
+        for digits in self.interesting()? {
+            let (idx, v) = digits?;
+            let idx = idx as usize;
+            if idx < 8 && password[idx].is_none() {
+                password[idx] = Some(hex_digit(v));
+                found += 1;
+                if found == 8 {
+                    return Ok(password.iter().flatten().collect::<String>().into());
                 }
             }
         }
-        Ok(password
-            .iter()
-            .map(|c| c.unwrap())
-            .collect::<String>()
-            .into())
+        Err(Error::Unsolved)
     }
 }
@@ -142,6 +142,6 @@ impl Day08 {
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
         self.process();
-        Ok(self.to_string().into())
+        Ok(common::ocr::recognize(&self.to_string())?.into())
     }
 }
@@ -1,3 +1,4 @@
+use common::search::{Search, SearchState};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -118,15 +119,19 @@ impl State {
             == 0;
         generators_final && microchips_final
     }
+}
+
+impl SearchState for State {
+    type Context = ();
+    // Which pair of items is which makes no difference, so states are keyed by the floors of
+    // each pair in sorted order
+    type Key = (usize, u64);
 
-    fn next_states(
-        &self,
-        in_floor: &mut Vec<(u32, u32)>,
-        seen: &mut HashSet<u64>, // (usize, u64)>,
-        work: &mut Vec<State>,
-    ) -> bool {
-        in_floor.clear();
+    fn key(&self) -> Self::Key {
+        (self.elevator(), self.pairs())
+    }
 
+    fn neighbors(&self, _: &mut (), out: &mut Vec<(Self, usize)>) {
         let elevator = self.elevator();
         let can_move_down = match elevator {
             0 => false,
@@ -138,17 +143,21 @@ impl State {
             3 => true,
             _ => unreachable!(),
         };
-        // let can_move_down = elevator != 0;
 
+        let mut in_floor = [(0, 0); 14];
+        let mut count = 0;
         for i in 0..7 {
             let typ = 1 << i;
             if (self.generators >> Self::SHIFTS[elevator]) & typ != 0 {
-                in_floor.push((typ, 0));
+                in_floor[count] = (typ, 0);
+                count += 1;
             }
             if (self.microchips >> Self::SHIFTS[elevator]) & typ != 0 {
-                in_floor.push((0, typ));
+                in_floor[count] = (0, typ);
+                count += 1;
             }
         }
+        let in_floor = &in_floor[..count];
         for (skip, (g1, m1)) in in_floor.iter().enumerate() {
             for (g2, m2) in in_floor.iter().skip(skip) {
                 macro_rules! check_move {
@@ -161,11 +170,8 @@ impl State {
                         let microchips = (self.microchips & m_mask_off) | m_mask_on;
 
                         let next_state = State::new($new_floor, generators, microchips);
-                        if next_state.is_valid() && seen.insert(next_state.pairs()) {
-                            if next_state.is_final() {
-                                return true;
-                            }
-                            work.push(next_state);
+                        if next_state.is_valid() {
+                            out.push((next_state, 1));
                         }
                     }};
                 }
@@ -179,7 +185,10 @@ impl State {
                 }
             }
         }
-        false
+    }
+
+    fn is_goal(&self, _: &()) -> bool {
+        self.is_final()
     }
 }
 
@@ -232,31 +241,8 @@ impl Day11 {
         Self::default()
     }
 
-    fn solve(&self) -> usize {
-        let initial_state = State::initial(&self.items);
-
-        let mut in_floor = Vec::new();
-
-        let mut work = Vec::new();
-        let mut next_work = Vec::new();
-        work.push(initial_state);
-        let mut seen = HashSet::default();
-        for steps in 1.. {
-            seen.clear();
-            next_work.clear();
-            while let Some(state) = work.pop() {
-                if state.next_states(&mut in_floor, &mut seen, &mut next_work) {
-                    return steps;
-                }
-            }
-            if next_work.is_empty() {
-                break;
-            }
-            // println!("{:016x} {}", seen.iter().max().unwrap(), seen.len());
-            std::mem::swap(&mut work, &mut next_work);
-        }
-
-        0
+    fn solve(&self) -> Result<usize, Error> {
+        Search::new(&mut ()).bfs(State::initial(&self.items)).cost()
     }
 }
 
@@ -306,7 +292,7 @@ impl Runner for Day11 {
 
 impl Day11 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.solve().into())
+        Ok(self.solve()?.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
@@ -332,6 +318,6 @@ impl Day11 {
                 floor: 0,
             });
         }
-        Ok(self.solve().into())
+        Ok(self.solve()?.into())
     }
 }
@@ -1,3 +1,4 @@
+use common::search::{Search, SearchState};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -18,62 +19,32 @@ impl Day13 {
         let bits = num.count_ones();
         bits % 2 == 0
     }
+}
 
-    fn find_paths<F>(&self, max_len: Option<usize>, stop_at: F) -> usize
-    where
-        F: Fn(isize, isize) -> bool,
-    {
-        let mut seen = std::collections::BTreeSet::new();
-        let mut paths = Vec::new();
-        let max_len = max_len.unwrap_or(usize::MAX);
-
-        let initial = (1isize, 1isize);
-        paths.push(vec![initial]);
-        seen.insert(initial);
-
-        let mut i = 0;
-        while i < paths.len() {
-            let last = paths[i][paths[i].len() - 1];
-            let x = last.0;
-            let y = last.1;
-
-            for dy in -1..=1 {
-                for dx in -1..=1 {
-                    if (dy == 0 && dx == 0) || (dy != 0 && dx != 0) {
-                        continue;
-                    }
-                    let x = x + dx;
-                    let y = y + dy;
-                    if x < 0 || y < 0 {
-                        continue;
-                    }
-
-                    if !self.is_open(x, y) {
-                        continue;
-                    }
+#[derive(Copy, Clone, Debug)]
+struct Pos(isize, isize);
 
-                    let next = (x, y);
-                    if seen.contains(&next) {
-                        continue;
-                    }
+impl SearchState for Pos {
+    type Context = Day13;
+    type Key = (isize, isize);
 
-                    let mut next_path = paths[i].clone();
-                    next_path.push(next);
-                    if stop_at(x, y) {
-                        return next_path.len() - 1;
-                    }
+    fn key(&self) -> Self::Key {
+        (self.0, self.1)
+    }
 
-                    seen.insert(next);
-                    if next_path.len() <= max_len {
-                        paths.push(next_path);
-                    }
-                }
+    fn neighbors(&self, day: &mut Day13, out: &mut Vec<(Self, usize)>) {
+        for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+            let (x, y) = (self.0 + dx, self.1 + dy);
+            if x >= 0 && y >= 0 && day.is_open(x, y) {
+                out.push((Pos(x, y), 1));
             }
-
-            i += 1;
         }
+    }
 
-        seen.len()
+    // Part 1 searches towards a known position from both ends and part 2 only counts what it
+    // reaches, so neither needs a goal test
+    fn is_goal(&self, _: &Day13) -> bool {
+        false
     }
 }
 
@@ -96,10 +67,14 @@ impl Runner for Day13 {
 
 impl Day13 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.find_paths(None, |x, y| x == 31 && y == 39).into())
+        let steps = Search::new(self)
+            .bidirectional(Pos(1, 1), Pos(31, 39))
+            .cost()?;
+        Ok(steps.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.find_paths(Some(50), |_, _| false).into())
+        let outcome = Search::new(self).limit(50).bfs(Pos(1, 1));
+        Ok(outcome.stats.visited.into())
     }
 }
@@ -1,8 +1,6 @@
+use common::md5::{self, Md5Search};
 #[allow(unused_imports)]
-use helper::{
-    print, println, Error, HashMap, HashSet, Lines, LinesOpt, MD5String, Output, RunOutput, Runner,
-};
-use std::fmt::Write;
+use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
 #[derive(Debug)]
 struct Hash {
@@ -11,7 +9,12 @@ struct Hash {
 }
 
 impl Hash {
-    fn new(digits: &[u8]) -> Self {
+    fn new(digest: [u8; 16]) -> Self {
+        let mut digits = [0; 32];
+        for (i, v) in digest.iter().enumerate() {
+            digits[i * 2] = v >> 4;
+            digits[i * 2 + 1] = v & 0xf;
+        }
         let mut hash = Hash {
             threes: Vec::new(),
             fives: Vec::new(),
@@ -45,8 +48,7 @@ impl Hash {
 #[derive(Default)]
 pub struct Day14 {
     hashes: Vec<Hash>,
-    md5: MD5String,
-    salt_len: usize,
+    search: Md5Search,
 }
 
 impl Day14 {
@@ -55,44 +57,18 @@ impl Day14 {
     }
 
     fn get_hash(&mut self, n: usize, part1: bool) -> Result<&Hash, Error> {
-        let mut hash: [u8; 32] = [0; 32];
-
-        while self.hashes.len() <= n {
-            self.md5.truncate(self.salt_len);
-            write!(&mut self.md5, "{}", self.hashes.len())?;
-
-            let result = if part1 {
-                self.md5.digest()
+        if self.hashes.len() <= n {
+            // Hash ahead in batches big enough to keep every thread busy, the lookahead will
+            // need them soon anyway
+            let start = self.hashes.len();
+            let end = (n + 1).max(start + 256 * md5::threads());
+            let search = if part1 {
+                self.search
             } else {
-                let mut md5 = self.md5;
-                let mut result: [u8; 16] = [0; 16];
-                for _ in 0..=2016 {
-                    result = md5.digest();
-                    md5.truncate_without_zero(0);
-                    for (i, v) in result.iter().enumerate() {
-                        macro_rules! set {
-                            ($i:expr, $v:expr) => {{
-                                if $v <= 9 {
-                                    hash[$i] = $v + b'0';
-                                } else {
-                                    hash[$i] = $v - 10 + b'a';
-                                }
-                            }};
-                        }
-
-                        set!(i * 2, v >> 4);
-                        set!(i * 2 + 1, v & 0xf);
-                    }
-                    md5.push_bytes(&hash)?;
-                }
-                result
+                self.search.stretch(2016)
             };
-
-            for (i, r) in result.iter().enumerate() {
-                hash[i * 2] = r >> 4;
-                hash[i * 2 + 1] = r & 0xf;
-            }
-            self.hashes.push(Hash::new(&hash));
+            self.hashes
+                .extend(search.map(start..end, |_, digest| Hash::new(digest))?);
         }
 
         Ok(&self.hashes[n])
@@ -127,8 +103,7 @@ impl Runner for Day14 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
         assert_eq!(lines.len(), 1);
-        self.salt_len = lines[0].len();
-        write!(&mut self.md5, "{}", lines[0])?;
+        self.search = Md5Search::new(&lines[0])?;
         Ok(())
     }
 
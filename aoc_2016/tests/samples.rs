@@ -0,0 +1 @@
+common::sample_tests!(aoc_2016::register, 2016);
@@ -1,3 +1,4 @@
+use common::cycle::{self, Cycle};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -11,41 +12,30 @@ impl Day06 {
         Self::default()
     }
 
-    fn realloc(&mut self) {
-        let max_idx = self
-            .banks
-            .iter()
-            .copied()
-            .enumerate()
-            .fold(
-                (0, usize::MIN),
-                |(idx, max), (i, v)| if v > max { (i, v) } else { (idx, max) },
-            )
-            .0;
-
-        let mut left = self.banks[max_idx];
-        self.banks[max_idx] = 0;
-        let mut idx = max_idx + 1;
-        let num_banks = self.banks.len();
-        while left > 0 {
-            self.banks[idx % num_banks] += 1;
-            idx += 1;
-            left -= 1;
-        }
+    fn find_cycle(&self) -> Result<Cycle, Error> {
+        cycle::brent(self.banks.clone(), |banks| realloc(banks))
     }
+}
 
-    fn find_cycle(&mut self) -> (usize, usize) {
-        let mut banks = HashMap::default();
-        banks.insert(self.banks.clone(), 0);
-        for steps in 1.. {
-            self.realloc();
+fn realloc(banks: &mut [usize]) {
+    let max_idx = banks
+        .iter()
+        .copied()
+        .enumerate()
+        .fold(
+            (0, usize::MIN),
+            |(idx, max), (i, v)| if v > max { (i, v) } else { (idx, max) },
+        )
+        .0;
 
-            let prev = *banks.entry(self.banks.clone()).or_insert(steps);
-            if prev != steps {
-                return (steps, steps - prev);
-            }
-        }
-        (0, 0)
+    let mut left = banks[max_idx];
+    banks[max_idx] = 0;
+    let mut idx = max_idx + 1;
+    let num_banks = banks.len();
+    while left > 0 {
+        banks[idx % num_banks] += 1;
+        idx += 1;
+        left -= 1;
     }
 }
 
@@ -70,10 +60,11 @@ impl Runner for Day06 {
 
 impl Day06 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.find_cycle().0.into())
+        let cycle = self.find_cycle()?;
+        Ok((cycle.start + cycle.len).into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.find_cycle().1.into())
+        Ok(self.find_cycle()?.len.into())
     }
 }
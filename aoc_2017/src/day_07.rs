@@ -1,3 +1,4 @@
+use common::dot::{Dot, GraphDump};
 use std::{fmt::Write, str::FromStr};
 
 #[allow(unused_imports)]
@@ -126,7 +127,10 @@ impl Day07 {
         while self.programs.len() > 1 {
             for program in programs.iter() {
                 if let Some(program) = self.programs.remove(program.name.as_str()) {
-                    if program.holding.len() == program.holding_names.len() {
+                    // The root has no parent and stays until everything is under it
+                    if program.holding.len() == program.holding_names.len()
+                        && !program.parent.is_empty()
+                    {
                         if let Some(parent) = self.programs.get_mut(program.parent.as_str()) {
                             parent.holding.push(program);
                         } else {
@@ -151,6 +155,22 @@ impl Day07 {
     }
 }
 
+impl GraphDump for Day07 {
+    fn dot(&self) -> Dot {
+        let mut dot = Dot::digraph("tower");
+        let mut names: Vec<&String> = self.programs.keys().collect();
+        names.sort();
+        for name in names {
+            let program = &self.programs[name];
+            dot.node(name, &[("label", &format!("{name}\n{}", program.weight))]);
+            for holding in program.holding_names.iter() {
+                dot.edge(name, holding, &[]);
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day07 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
@@ -1,3 +1,4 @@
+use common::dot::{Dot, GraphDump};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 use std::str::FromStr;
@@ -58,6 +59,20 @@ impl Day12 {
     }
 }
 
+impl GraphDump for Day12 {
+    fn dot(&self) -> Dot {
+        let mut dot = Dot::graph("pipes");
+        for (from, program) in self.programs.iter().enumerate() {
+            dot.node(from, &[]);
+            // Pipes are listed from both ends
+            for to in program.pipes.iter().copied().filter(|to| *to >= from) {
+                dot.edge(from, to, &[]);
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day12 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
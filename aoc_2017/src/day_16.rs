@@ -1,3 +1,4 @@
+use common::cycle::Detector;
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 use std::str::FromStr;
@@ -117,19 +118,16 @@ impl Day16 {
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let mut seen = Vec::new();
-        seen.push(self.to_string());
         const ITERS: usize = 1_000_000_000;
-        for step in 1..ITERS {
-            self.dance();
+        let mut detector = Detector::new();
+        let mut seen: Vec<String> = Vec::new();
+        loop {
             let cur = self.to_string();
-            if let Some(start) = seen.iter().position(|s| s == &cur) {
-                let cycle_len = step - start;
-                let idx = start + ((ITERS - step) % cycle_len);
-                return Ok(seen[idx].clone().into());
+            if let Some(cycle) = detector.push(cur.clone()) {
+                return Ok(seen[cycle.fast_forward(ITERS)].clone().into());
             }
             seen.push(cur);
+            self.dance();
         }
-        Err(Error::Unsolved)
     }
 }
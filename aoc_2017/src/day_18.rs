@@ -1,170 +1,10 @@
+use asm::{duet::Duet, Machine, State};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
-use std::collections::VecDeque;
-use std::str::FromStr;
-
-#[derive(Clone, Debug)]
-enum Value {
-    Reg(usize),
-    Imm(isize),
-}
-
-impl Value {
-    fn as_reg(&self) -> usize {
-        if let Self::Reg(r) = self {
-            return *r;
-        }
-        panic!("Not a register");
-    }
-
-    fn value(&self, registers: &[isize; 26]) -> isize {
-        match self {
-            Self::Reg(r) => registers[*r],
-            Self::Imm(v) => *v,
-        }
-    }
-}
-
-impl FromStr for Value {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(num) = s.parse::<isize>() {
-            Ok(Self::Imm(num))
-        } else if s.len() == 1 {
-            let r = s.chars().nth(0).unwrap();
-            if !r.is_ascii_lowercase() {
-                Err(Error::InvalidInput(s.to_string()))
-            } else {
-                Ok(Self::Reg((r as u8 - b'a') as usize))
-            }
-        } else {
-            Err(Error::InvalidInput(s.to_string()))
-        }
-    }
-}
-
-#[derive(Clone, Debug)]
-enum Instruction {
-    Set(Value, Value),
-    Add(Value, Value),
-    Mul(Value, Value),
-    Mod(Value, Value),
-    Jgz(Value, Value),
-    Snd(Value),
-    Rcv(Value),
-}
-
-impl FromStr for Instruction {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(" ").collect();
-        match parts[0] {
-            "set" => Ok(Self::Set(parts[1].parse()?, parts[2].parse()?)),
-            "add" => Ok(Self::Add(parts[1].parse()?, parts[2].parse()?)),
-            "mul" => Ok(Self::Mul(parts[1].parse()?, parts[2].parse()?)),
-            "mod" => Ok(Self::Mod(parts[1].parse()?, parts[2].parse()?)),
-            "jgz" => Ok(Self::Jgz(parts[1].parse()?, parts[2].parse()?)),
-            "snd" => Ok(Self::Snd(parts[1].parse()?)),
-            "rcv" => Ok(Self::Rcv(parts[1].parse()?)),
-            _ => Err(Error::InvalidInput(s.to_string())),
-        }
-    }
-}
-
-struct Cpu<'a> {
-    id: isize,
-    registers: [isize; 26],
-    instructions: &'a [Instruction],
-    queue: VecDeque<isize>,
-    waiting: bool,
-    pc: isize,
-}
-
-impl<'a> Cpu<'a> {
-    fn new(id: isize, instructions: &'a [Instruction]) -> Self {
-        Self {
-            id,
-            registers: [0; 26],
-            instructions,
-            queue: VecDeque::new(),
-            waiting: false,
-            pc: 0,
-        }
-    }
-
-    fn part1(&mut self) -> isize {
-        let mut last_freq: isize = 0;
-        while let Some(inst) = self.instructions.get(self.pc as usize) {
-            let mut next_pc = self.pc + 1;
-            match inst {
-                Instruction::Set(x, y) => self.registers[x.as_reg()] = y.value(&self.registers),
-                Instruction::Add(x, y) => self.registers[x.as_reg()] += y.value(&self.registers),
-                Instruction::Mul(x, y) => self.registers[x.as_reg()] *= y.value(&self.registers),
-                Instruction::Mod(x, y) => self.registers[x.as_reg()] %= y.value(&self.registers),
-                Instruction::Jgz(x, y) => {
-                    if self.registers[x.as_reg()] > 0 {
-                        next_pc = self.pc + y.value(&self.registers)
-                    }
-                }
-                Instruction::Snd(x) => last_freq = x.value(&self.registers),
-                Instruction::Rcv(x) => {
-                    if x.value(&self.registers) != 0 {
-                        println!("Last Sound Played: {:?}", last_freq);
-                        break;
-                    }
-                }
-            }
-
-            self.pc = next_pc;
-        }
-
-        last_freq
-    }
-
-    fn part2(&mut self) -> Option<isize> {
-        if let Some(inst) = self.instructions.get(self.pc as usize) {
-            let mut next_pc = self.pc + 1;
-            let mut send_value = None;
-            match inst {
-                Instruction::Set(x, y) => self.registers[x.as_reg()] = y.value(&self.registers),
-                Instruction::Add(x, y) => self.registers[x.as_reg()] += y.value(&self.registers),
-                Instruction::Mul(x, y) => self.registers[x.as_reg()] *= y.value(&self.registers),
-                Instruction::Mod(x, y) => self.registers[x.as_reg()] %= y.value(&self.registers),
-                Instruction::Jgz(x, y) => {
-                    if x.value(&self.registers) > 0 {
-                        next_pc = self.pc + y.value(&self.registers)
-                    }
-                }
-                Instruction::Snd(x) => send_value = Some(x.value(&self.registers)),
-                Instruction::Rcv(x) => {
-                    if !self.queue.is_empty() {
-                        self.waiting = false;
-                        self.registers[x.as_reg()] = self.queue.pop_front().unwrap();
-                    } else {
-                        self.waiting = true;
-                        return None;
-                    }
-                }
-            }
-
-            self.pc = next_pc;
-            send_value
-        } else {
-            self.waiting = true;
-            None
-        }
-    }
-
-    fn can_run(&self) -> bool {
-        !self.waiting || !self.queue.is_empty()
-    }
-}
 
 #[derive(Default)]
 pub struct Day18 {
-    instructions: Vec<Instruction>,
+    machine: Machine<Duet>,
 }
 
 impl Day18 {
@@ -176,10 +16,7 @@ impl Day18 {
 impl Runner for Day18 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for line in lines.iter() {
-            self.instructions.push(line.parse()?);
-        }
-
+        self.machine = Machine::parse(lines.iter())?;
         Ok(())
     }
 
@@ -194,34 +31,48 @@ impl Runner for Day18 {
 
 impl Day18 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        let mut cpu = Cpu::new(0, &self.instructions);
-        Ok(cpu.part1().into())
+        // In part 1 `snd` plays a sound and `rcv` only recovers it when its register is non-zero
+        let cpu = &mut self.machine;
+        let mut last_freq = 0;
+        while let Some(inst) = cpu.current().copied() {
+            if let Duet::Rcv(x) = inst {
+                if cpu.registers[x] != 0 {
+                    println!("Last Sound Played: {:?}", last_freq);
+                    break;
+                }
+                cpu.pc += 1;
+                continue;
+            }
+            if let State::Output(freq) = cpu.step() {
+                last_freq = freq;
+            }
+        }
+
+        Ok(last_freq.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let mut cpus = [
-            Cpu::new(0, &self.instructions),
-            Cpu::new(1, &self.instructions),
-        ];
-        for cpu in cpus.iter_mut() {
-            cpu.registers[(b'p' - b'a') as usize] = cpu.id;
+        let mut cpus = [self.machine.clone(), self.machine.clone()];
+        for (id, cpu) in cpus.iter_mut().enumerate() {
+            cpu.registers[(b'p' - b'a') as usize] = id as isize;
         }
         let mut sends = [0, 0];
+        let mut waiting = [false, false];
 
         loop {
-            let send_0 = cpus[0].part2();
-            let send_1 = cpus[1].part2();
-
-            if let Some(send_0) = send_0 {
-                cpus[1].queue.push_back(send_0);
-                sends[0] += 1;
-            }
-            if let Some(send_1) = send_1 {
-                cpus[0].queue.push_back(send_1);
-                sends[1] += 1;
+            for id in 0..2 {
+                waiting[id] = match cpus[id].step() {
+                    State::Output(v) => {
+                        cpus[1 - id].input.push_back(v);
+                        sends[id] += 1;
+                        false
+                    }
+                    State::Running => false,
+                    State::WaitingForInput | State::Halted => true,
+                };
             }
 
-            if !cpus[0].can_run() && !cpus[1].can_run() {
+            if (0..2).all(|id| waiting[id] && cpus[id].input.is_empty()) {
                 break;
             }
         }
@@ -37,10 +37,10 @@ impl Runner for Day25 {
         for lines in lines[3..].chunks(10) {
             let state_name = (lines[0].chars().nth(9).unwrap() as u8 - b'A') as usize;
             let state = &mut self.states[state_name];
-            state.steps[0].write = &lines[2][22..23] == "0";
+            state.steps[0].write = &lines[2][22..23] == "1";
             state.steps[0].pos_delta = if &lines[3][27..] == "right." { 1 } else { -1 };
             state.steps[0].next_state = (lines[4].chars().nth(26).unwrap() as u8 - b'A') as usize;
-            state.steps[1].write = &lines[6][22..23] == "0";
+            state.steps[1].write = &lines[6][22..23] == "1";
             state.steps[1].pos_delta = if &lines[7][27..] == "right." { 1 } else { -1 };
             state.steps[1].next_state = (lines[8].chars().nth(26).unwrap() as u8 - b'A') as usize;
         }
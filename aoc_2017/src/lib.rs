@@ -1,3 +1,4 @@
+use common::dot::{dump, NewDump};
 use helper::NewRunner;
 use std::collections::BTreeMap;
 
@@ -56,3 +57,7 @@ pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2017, 25), (1, || Box::new(day_25::Day25::new())));
 }
 
+pub fn register_dumps(dumps: &mut BTreeMap<(usize, usize), NewDump>) {
+    dumps.insert((2017, 7), |input| dump(day_07::Day07::new(), input));
+    dumps.insert((2017, 12), |input| dump(day_12::Day12::new(), input));
+}
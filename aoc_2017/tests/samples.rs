@@ -0,0 +1 @@
+common::sample_tests!(aoc_2017::register, 2017);
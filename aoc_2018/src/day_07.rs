@@ -1,3 +1,4 @@
+use common::dot::{Dot, GraphDump};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 use std::collections::{BTreeMap, BTreeSet};
@@ -67,6 +68,20 @@ impl Day07 {
     }
 }
 
+impl GraphDump for Day07 {
+    fn dot(&self) -> Dot {
+        let name = |step: usize| (step as u8 + b'A') as char;
+        let mut dot = Dot::digraph("steps");
+        for (step, depends_on) in self.steps.iter().enumerate() {
+            dot.node(name(step), &[]);
+            for from in depends_on.iter() {
+                dot.edge(name(*from), name(step), &[]);
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day07 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
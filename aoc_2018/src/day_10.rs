@@ -133,7 +133,7 @@ impl Runner for Day10 {
 impl Day10 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         let t = self.find_message_time();
-        Ok(self.get_message(t).into())
+        Ok(common::ocr::recognize(&self.get_message(t))?.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
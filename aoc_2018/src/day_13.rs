@@ -1,3 +1,4 @@
+use common::frames::{self, Frame, BLACK, GREY, RED, YELLOW};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -21,7 +22,24 @@ impl Day13 {
         Self::default()
     }
 
+    fn frame(&self) -> Frame {
+        let width = self.map.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut frame = Frame::new(width, self.map.len(), BLACK);
+        for (y, row) in self.map.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if *c != ' ' {
+                    frame.set(x, y, GREY);
+                }
+            }
+        }
+        for cart in self.carts.iter() {
+            frame.set(cart.x, cart.y, if cart.crashed { RED } else { YELLOW });
+        }
+        frame
+    }
+
     fn move_carts(&mut self, first_crash: bool) -> Option<(usize, usize)> {
+        frames::record(|| self.frame());
         self.carts.sort();
         for i in 0..self.carts.len() {
             if self.carts[i].crashed {
@@ -1,3 +1,4 @@
+use common::grid::{Cell, Grid};
 #[allow(unused_imports)]
 use helper::{
     print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, Point2D, RunOutput, Runner,
@@ -5,7 +6,7 @@ use helper::{
 
 #[derive(Clone, Default)]
 struct Map {
-    grid: Vec<Vec<Tile>>,
+    grid: Grid<Tile>,
     empty_key: usize,
 }
 
@@ -17,9 +18,30 @@ enum Tile {
     Goblin(isize),
 }
 
+impl Cell for Tile {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'E' => Some(Tile::Elf(200)),
+            'G' => Some(Tile::Goblin(200)),
+            '.' => Some(Tile::Empty(0)),
+            '#' => Some(Tile::Wall),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Empty(_) => '.',
+            Tile::Wall => '#',
+            Tile::Elf(_) => 'E',
+            Tile::Goblin(_) => 'G',
+        }
+    }
+}
+
 impl Map {
     fn get(&self, p: Point2D<u8>) -> Tile {
-        self.grid[p.y as usize][p.x as usize]
+        self.grid[(p.x as usize, p.y as usize)]
     }
 
     fn find_path(&mut self, from: Point2D<u8>, to: Point2D<u8>) -> Option<(u8, Point2D<u8>)> {
@@ -39,7 +61,7 @@ impl Map {
                 return Some((0, next));
             }
             queue.push((next, 1, next));
-            self.grid[next.y as usize][next.x as usize] = Tile::Empty(self.empty_key);
+            self.grid[(next.x as usize, next.y as usize)] = Tile::Empty(self.empty_key);
         }
 
         let mut i = 0;
@@ -57,7 +79,7 @@ impl Map {
                 }
 
                 queue.push((queue[i].0, queue[i].1 + 1, next));
-                self.grid[next.y as usize][next.x as usize] = Tile::Empty(self.empty_key);
+                self.grid[(next.x as usize, next.y as usize)] = Tile::Empty(self.empty_key);
             }
             i += 1;
         }
@@ -130,10 +152,10 @@ impl Map {
                 }
 
                 if let Some(best_move) = best_move {
-                    self.grid[units[i].y as usize][units[i].x as usize] = Tile::Empty(0);
+                    self.grid[(units[i].x as usize, units[i].y as usize)] = Tile::Empty(0);
                     units[i].x = best_move.x;
                     units[i].y = best_move.y;
-                    self.grid[units[i].y as usize][units[i].x as usize] = units[i].tile();
+                    self.grid[(units[i].x as usize, units[i].y as usize)] = units[i].tile();
                 }
 
                 // Look for attacks
@@ -164,7 +186,7 @@ impl Map {
                     }
                 }
                 units[min_unit].health -= units[i].attack_power;
-                self.grid[units[min_unit].y as usize][units[min_unit].x as usize] =
+                self.grid[(units[min_unit].x as usize, units[min_unit].y as usize)] =
                     units[min_unit].tile();
             }
         }
@@ -271,36 +293,17 @@ impl Day15 {
 impl Runner for Day15 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for (y, line) in lines.iter().enumerate() {
-            let mut row = Vec::new();
-            for (x, c) in line.chars().enumerate() {
-                row.push(match c {
-                    'E' => {
-                        self.units.push(Unit {
-                            y: y as u8,
-                            x: x as u8,
-                            elf: true,
-                            health: 200,
-                            attack_power: 3,
-                        });
-                        Tile::Elf(200)
-                    }
-                    'G' => {
-                        self.units.push(Unit {
-                            y: y as u8,
-                            x: x as u8,
-                            elf: false,
-                            health: 200,
-                            attack_power: 3,
-                        });
-                        Tile::Goblin(200)
-                    }
-                    '.' => Tile::Empty(0),
-                    '#' => Tile::Wall,
-                    _ => return Err(Error::InvalidInput(c.into())),
+        self.map.grid = Grid::parse(lines.iter())?;
+        for ((x, y), tile) in self.map.grid.iter() {
+            if let Tile::Elf(health) | Tile::Goblin(health) = *tile {
+                self.units.push(Unit {
+                    y: y as u8,
+                    x: x as u8,
+                    elf: matches!(tile, Tile::Elf(_)),
+                    health,
+                    attack_power: 3,
                 });
             }
-            self.map.grid.push(row);
         }
         Ok(())
     }
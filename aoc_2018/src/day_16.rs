@@ -1,106 +1,7 @@
+use crate::elfcode::{Cpu, Instruction, OpCode};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
-#[derive(Clone)]
-struct Cpu {
-    registers: [usize; 4],
-}
-
-impl Cpu {
-    fn addr(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] + self.registers[b];
-    }
-
-    fn addi(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] + b;
-    }
-
-    fn mulr(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] * self.registers[b];
-    }
-
-    fn muli(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] * b;
-    }
-
-    fn banr(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] & self.registers[b];
-    }
-
-    fn bani(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] & b;
-    }
-
-    fn borr(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] | self.registers[b];
-    }
-
-    fn bori(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = self.registers[a] | b;
-    }
-
-    fn setr(&mut self, a: usize, _b: usize, c: usize) {
-        self.registers[c] = self.registers[a];
-    }
-
-    fn seti(&mut self, a: usize, _b: usize, c: usize) {
-        self.registers[c] = a;
-    }
-
-    fn gtir(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = if a > self.registers[b] { 1 } else { 0 };
-    }
-
-    fn gtri(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = if self.registers[a] > b { 1 } else { 0 };
-    }
-
-    fn gtrr(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = if self.registers[a] > self.registers[b] {
-            1
-        } else {
-            0
-        };
-    }
-
-    fn eqir(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = if a == self.registers[b] { 1 } else { 0 };
-    }
-
-    fn eqri(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = if self.registers[a] == b { 1 } else { 0 };
-    }
-
-    fn eqrr(&mut self, a: usize, b: usize, c: usize) {
-        self.registers[c] = if self.registers[a] == self.registers[b] {
-            1
-        } else {
-            0
-        };
-    }
-}
-
-#[repr(usize)]
-#[derive(Debug, Copy, Clone)]
-enum OpCode {
-    AddR,
-    AddI,
-    MulR,
-    MulI,
-    BanR,
-    BanI,
-    BorR,
-    BorI,
-    SetR,
-    SetI,
-    GtIR,
-    GtRI,
-    GtRR,
-    EqIR,
-    EqRI,
-    EqRR,
-}
-
 #[derive(Default)]
 pub struct Day16 {
     part1: Vec<([usize; 4], [usize; 4], [usize; 4])>,
@@ -114,35 +15,15 @@ impl Day16 {
     }
 
     fn map_op_codes(&mut self) -> usize {
-        let operations = [
-            Cpu::addr,
-            Cpu::addi,
-            Cpu::mulr,
-            Cpu::muli,
-            Cpu::banr,
-            Cpu::bani,
-            Cpu::borr,
-            Cpu::bori,
-            Cpu::setr,
-            Cpu::seti,
-            Cpu::gtir,
-            Cpu::gtri,
-            Cpu::gtrr,
-            Cpu::eqir,
-            Cpu::eqri,
-            Cpu::eqrr,
-        ];
-
         let mut op_codes = [[(false, false); 16]; 16];
 
         let mut three_or_more = 0;
         for (before, inst, after) in self.part1.iter() {
-            let initial = Cpu { registers: *before };
             let mut num_matches = 0;
-            for (op_code, func) in operations.iter().enumerate() {
-                let mut test = initial.clone();
-                func(&mut test, inst[1], inst[2], inst[3]);
-                if test.registers.eq(after) {
+            for (op_code, op) in OpCode::ALL.iter().enumerate() {
+                let mut test = *before;
+                op.execute(&mut test, inst[1], inst[2], inst[3]);
+                if test.eq(after) {
                     num_matches += 1;
                     op_codes[op_code][inst[0]].0 = true;
                 } else {
@@ -178,27 +59,10 @@ impl Day16 {
                 }
             }
         }
-        for op_code in [
-            OpCode::AddR,
-            OpCode::AddI,
-            OpCode::MulR,
-            OpCode::MulI,
-            OpCode::BanR,
-            OpCode::BanI,
-            OpCode::BorR,
-            OpCode::BorI,
-            OpCode::SetR,
-            OpCode::SetI,
-            OpCode::GtIR,
-            OpCode::GtRI,
-            OpCode::GtRR,
-            OpCode::EqIR,
-            OpCode::EqRI,
-            OpCode::EqRR,
-        ] {
-            match mapped_op_codes[op_code as usize] {
+        for (op_code, mapped) in OpCode::ALL.iter().zip(mapped_op_codes.iter()) {
+            match mapped {
                 Some(op_num) => {
-                    let _ = self.op_codes.insert(op_num, op_code);
+                    let _ = self.op_codes.insert(*op_num, *op_code);
                 }
                 None => unreachable!(),
             }
@@ -253,29 +117,16 @@ impl Day16 {
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
         self.map_op_codes();
-        let mut cpu = Cpu {
-            registers: [0, 0, 0, 0],
-        };
+        let mut cpu = Cpu::default();
         for inst in self.part2.iter() {
-            match self.op_codes.get(&inst[0]).unwrap() {
-                OpCode::AddR => cpu.addr(inst[1], inst[2], inst[3]),
-                OpCode::AddI => cpu.addi(inst[1], inst[2], inst[3]),
-                OpCode::MulR => cpu.mulr(inst[1], inst[2], inst[3]),
-                OpCode::MulI => cpu.muli(inst[1], inst[2], inst[3]),
-                OpCode::BanR => cpu.banr(inst[1], inst[2], inst[3]),
-                OpCode::BanI => cpu.bani(inst[1], inst[2], inst[3]),
-                OpCode::BorR => cpu.borr(inst[1], inst[2], inst[3]),
-                OpCode::BorI => cpu.bori(inst[1], inst[2], inst[3]),
-                OpCode::SetR => cpu.setr(inst[1], inst[2], inst[3]),
-                OpCode::SetI => cpu.seti(inst[1], inst[2], inst[3]),
-                OpCode::GtIR => cpu.gtir(inst[1], inst[2], inst[3]),
-                OpCode::GtRI => cpu.gtri(inst[1], inst[2], inst[3]),
-                OpCode::GtRR => cpu.gtrr(inst[1], inst[2], inst[3]),
-                OpCode::EqIR => cpu.eqir(inst[1], inst[2], inst[3]),
-                OpCode::EqRI => cpu.eqri(inst[1], inst[2], inst[3]),
-                OpCode::EqRR => cpu.eqrr(inst[1], inst[2], inst[3]),
-            }
+            cpu.program.push(Instruction {
+                opcode: *self.op_codes.get(&inst[0]).unwrap(),
+                a: inst[1],
+                b: inst[2],
+                c: inst[3],
+            });
         }
+        cpu.run();
         Ok(cpu.registers[0].into())
     }
 }
@@ -1,3 +1,4 @@
+use common::frames::{self, Frame, BLACK, BLUE, GREY, WHITE, YELLOW};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 // use std::collections::BTreeSet;
@@ -135,6 +136,24 @@ impl Map {
         }
     }
 
+    // Clay grey, settled water blue and flowing water white, cropped to the veins
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(self.x1 - self.x0 + 1, self.tiles.len(), BLACK);
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, c) in row[self.x0..].iter().enumerate() {
+                let colour = match c {
+                    '#' => GREY,
+                    '~' => BLUE,
+                    '|' => WHITE,
+                    '+' => YELLOW,
+                    _ => continue,
+                };
+                frame.set(x, y, colour);
+            }
+        }
+        frame
+    }
+
     fn water_tiles(&self, part1: bool) -> usize {
         let mut tiles = 0;
         for y in self.y0..=self.y1 {
@@ -150,6 +169,7 @@ impl Map {
     }
 
     fn fall(&mut self) -> bool {
+        frames::record(|| self.frame());
         let mut done = true;
         let mut neighbors = Vec::new();
         let mut falling: Vec<(usize, usize)> = self.falling.iter().copied().collect();
@@ -1,10 +1,12 @@
+use common::cycle;
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
+type Tiles = Vec<Vec<char>>;
+
 #[derive(Default)]
 pub struct Day18 {
-    cur: usize,
-    tiles: [Vec<Vec<char>>; 2],
+    tiles: Tiles,
     neighbor_cache: HashMap<(usize, usize), Vec<(usize, usize)>>,
 }
 
@@ -13,13 +15,13 @@ impl Day18 {
         Self::default()
     }
 
-    fn get_counts(&self, x: usize, y: usize) -> (char, usize, usize, usize) {
+    fn get_counts(&self, tiles: &Tiles, x: usize, y: usize) -> (char, usize, usize, usize) {
         let mut open = 0;
         let mut tree = 0;
         let mut lumberyard = 0;
 
         for neighbor in self.neighbor_cache.get(&(x, y)).unwrap() {
-            match self.tiles[self.cur][neighbor.1][neighbor.0] {
+            match tiles[neighbor.1][neighbor.0] {
                 '.' => open += 1,
                 '|' => tree += 1,
                 '#' => lumberyard += 1,
@@ -27,15 +29,15 @@ impl Day18 {
             }
         }
 
-        (self.tiles[self.cur][y][x], open, tree, lumberyard)
+        (tiles[y][x], open, tree, lumberyard)
     }
 
-    fn get_resource_counts(&self) -> (usize, usize) {
+    fn get_resource_counts(tiles: &Tiles) -> (usize, usize) {
         let mut trees = 0;
         let mut lumberyards = 0;
-        for y in 0..self.tiles[self.cur].len() {
-            for x in 0..self.tiles[self.cur][y].len() {
-                match self.tiles[self.cur][y][x] {
+        for row in tiles.iter() {
+            for tile in row.iter() {
+                match tile {
                     '|' => trees += 1,
                     '#' => lumberyards += 1,
                     _ => {}
@@ -46,39 +48,29 @@ impl Day18 {
         (trees, lumberyards)
     }
 
-    fn mutate(&mut self) {
-        let next = 1 - self.cur;
-
-        for y in 0..self.tiles[self.cur].len() {
-            for x in 0..self.tiles[self.cur][y].len() {
-                let (c, _open, tree, lumberyard) = self.get_counts(x, y);
-                if c == '.' {
-                    if tree >= 3 {
-                        self.tiles[next][y][x] = '|';
-                    } else {
-                        self.tiles[next][y][x] = '.';
-                    }
-                } else if c == '|' {
-                    if lumberyard >= 3 {
-                        self.tiles[next][y][x] = '#';
-                    } else {
-                        self.tiles[next][y][x] = '|';
-                    }
-                } else if c == '#' {
-                    if lumberyard >= 1 && tree >= 1 {
-                        self.tiles[next][y][x] = '#';
-                    } else {
-                        self.tiles[next][y][x] = '.';
-                    }
-                }
-            }
+    fn next_tile(&self, tiles: &Tiles, x: usize, y: usize) -> char {
+        let (c, _open, tree, lumberyard) = self.get_counts(tiles, x, y);
+        match c {
+            '.' if tree >= 3 => '|',
+            '|' if lumberyard >= 3 => '#',
+            '#' if lumberyard == 0 || tree == 0 => '.',
+            c => c,
         }
-        self.cur = next;
+    }
+
+    fn mutate(&self, tiles: &mut Tiles) {
+        *tiles = (0..tiles.len())
+            .map(|y| {
+                (0..tiles[y].len())
+                    .map(|x| self.next_tile(tiles, x, y))
+                    .collect()
+            })
+            .collect();
     }
 
     fn build_neighbors_map(&mut self) {
-        for y in 0..self.tiles[0].len() {
-            for x in 0..self.tiles[0][0].len() {
+        for y in 0..self.tiles.len() {
+            for x in 0..self.tiles[0].len() {
                 let mut neighbors = Vec::new();
                 for dy in -1..=1 {
                     for dx in -1..=1 {
@@ -93,7 +85,7 @@ impl Day18 {
                         }
                         let x = x as usize;
                         let y = y as usize;
-                        if x >= self.tiles[0][0].len() || y >= self.tiles[0].len() {
+                        if x >= self.tiles[0].len() || y >= self.tiles.len() {
                             continue;
                         }
 
@@ -106,25 +98,10 @@ impl Day18 {
         }
     }
 
-    fn simulate(&mut self, minutes: usize) -> usize {
-        let mut seen = Vec::new();
-
-        seen.push(self.tiles[self.cur].clone());
-        for min in 1..=minutes {
-            self.mutate();
-            for i in 0..seen.len() {
-                if seen[i].eq(&self.tiles[self.cur]) {
-                    let idx = ((minutes - min) % (min - i)) + i;
-                    self.tiles[self.cur] = seen[idx].clone();
-                    let (num_trees, num_lumberyards) = self.get_resource_counts();
-                    return num_trees * num_lumberyards;
-                }
-            }
-            seen.push(self.tiles[self.cur].clone());
-        }
-
-        let (num_trees, num_lumberyards) = self.get_resource_counts();
-        num_trees * num_lumberyards
+    fn simulate(&self, minutes: usize) -> Result<usize, Error> {
+        let history = cycle::find(self.tiles.clone(), |tiles| self.mutate(tiles))?;
+        let (num_trees, num_lumberyards) = Self::get_resource_counts(history.nth(minutes));
+        Ok(num_trees * num_lumberyards)
     }
 }
 
@@ -132,9 +109,7 @@ impl Runner for Day18 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
         for line in lines.iter() {
-            let line: Vec<char> = line.chars().collect();
-            self.tiles[1].push(vec!['.'; line.len()]);
-            self.tiles[0].push(line);
+            self.tiles.push(line.chars().collect());
         }
         Ok(())
     }
@@ -151,11 +126,16 @@ impl Runner for Day18 {
 impl Day18 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         self.build_neighbors_map();
-        Ok(self.simulate(10).into())
+        let mut tiles = self.tiles.clone();
+        for _ in 0..10 {
+            self.mutate(&mut tiles);
+        }
+        let (num_trees, num_lumberyards) = Self::get_resource_counts(&tiles);
+        Ok((num_trees * num_lumberyards).into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
         self.build_neighbors_map();
-        Ok(self.simulate(1000000000).into())
+        Ok(self.simulate(1000000000)?.into())
     }
 }
@@ -1,65 +1,10 @@
+use crate::elfcode::{Cpu, Hook, Instruction, OpCode};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
-use std::str::FromStr;
-
-#[derive(Debug, Copy, Clone)]
-enum OpCode {
-    AddR,
-    AddI,
-    MulR,
-    MulI,
-    BanR,
-    BanI,
-    BorR,
-    BorI,
-    SetR,
-    SetI,
-    GtIR,
-    GtRI,
-    GtRR,
-    EqIR,
-    EqRI,
-    EqRR,
-}
-
-struct Instruction {
-    opcode: OpCode,
-    a: usize,
-}
-
-impl FromStr for Instruction {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(" ").collect();
-
-        let opcode = match parts[0] {
-            "addr" => OpCode::AddR,
-            "addi" => OpCode::AddI,
-            "mulr" => OpCode::MulR,
-            "muli" => OpCode::MulI,
-            "banr" => OpCode::BanR,
-            "bani" => OpCode::BanI,
-            "borr" => OpCode::BorR,
-            "bori" => OpCode::BorI,
-            "setr" => OpCode::SetR,
-            "seti" => OpCode::SetI,
-            "gtir" => OpCode::GtIR,
-            "gtri" => OpCode::GtRI,
-            "gtrr" => OpCode::GtRR,
-            "eqir" => OpCode::EqIR,
-            "eqri" => OpCode::EqRI,
-            "eqrr" => OpCode::EqRR,
-            _ => return Err(Error::InvalidInput(s.into())),
-        };
-        let a = parts[1].parse()?;
-        Ok(Self { opcode, a })
-    }
-}
 
 #[derive(Default)]
 pub struct Day21 {
-    instructions: Vec<Instruction>,
+    cpu: Cpu,
 }
 
 impl Day21 {
@@ -67,45 +12,72 @@ impl Day21 {
         Self::default()
     }
 
-    fn find(&self, first: bool) -> usize {
-        let n: usize = 16777215;
-        let a = if let Instruction {
-            opcode: OpCode::SetI,
-            a,
-        } = self.instructions[7]
-        {
-            a
-        } else {
-            panic!("Could not find constant");
-        };
-        let x = 65899;
-        let x2 = (x * x) & n;
-        let x3 = (x2 * x) & n;
+    // The only use of register 0 is an `eqrr` halting check.  Record the value it is compared
+    // against each time it is reached; the first one halts soonest and the last one before a
+    // repeat halts latest.
+    fn find(&mut self, first: bool) -> Result<usize, Error> {
+        let program = &self.cpu.program;
+        let (check_pc, check_reg) = program
+            .iter()
+            .enumerate()
+            .find_map(|(pc, inst)| match inst {
+                Instruction {
+                    opcode: OpCode::EqRR,
+                    a: 0,
+                    b,
+                    ..
+                } => Some((pc, *b)),
+                Instruction {
+                    opcode: OpCode::EqRR,
+                    a,
+                    b: 0,
+                    ..
+                } => Some((pc, *a)),
+                _ => None,
+            })
+            .ok_or_else(|| Error::Runner("Could not find halting check".into()))?;
+
+        // The hot loop divides by 256 by counting up until (q + 1) * 256 > n.  Find the
+        // `addi q 1 t; muli t 256 t; gtrr t n t` sequence so it can be short cut.
+        let divide = program.windows(3).enumerate().find_map(|(pc, w)| {
+            match (w[0].opcode, w[1].opcode, w[2].opcode) {
+                (OpCode::AddI, OpCode::MulI, OpCode::GtRR)
+                    if w[0].b == 1 && w[1].b == 256 && w[1].a == w[0].c && w[2].a == w[0].c =>
+                {
+                    Some((pc, w[0].a, w[2].b))
+                }
+                _ => None,
+            }
+        });
 
-        let mut prev_r5;
-        let mut r5 = 0;
         let mut seen = HashSet::default();
-        loop {
-            prev_r5 = r5;
-            r5 = (((r5 >> 16) | 1) * x + ((r5 >> 8) & 255) * x2 + ((r5 & 255) + a) * x3) & n;
-            if first && seen.is_empty() {
-                return r5;
+        let mut last = None;
+        self.cpu.run_with_hooks(|cpu| {
+            if let Some((divide_pc, q, n)) = divide {
+                if cpu.pc == divide_pc {
+                    cpu.registers[q] = cpu.registers[n] / 256;
+                }
             }
-            if !seen.insert(r5) {
-                return prev_r5;
+            if cpu.pc == check_pc {
+                let value = cpu.registers[check_reg];
+                if !seen.insert(value) {
+                    return Hook::Stop;
+                }
+                last = Some(value);
+                if first {
+                    return Hook::Stop;
+                }
             }
-        }
+            Hook::Continue
+        });
+
+        last.ok_or(Error::Unsolved)
     }
 }
 
 impl Runner for Day21 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
-        let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for line in lines.iter() {
-            if line.strip_prefix("#ip ").is_none() {
-                self.instructions.push(line.parse()?);
-            }
-        }
+        self.cpu.load(Lines::from_bufread(file, LinesOpt::RAW)?)?;
         Ok(())
     }
 
@@ -120,10 +92,10 @@ impl Runner for Day21 {
 
 impl Day21 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.find(true).into())
+        Ok(self.find(true)?.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.find(false).into())
+        Ok(self.find(false)?.into())
     }
 }
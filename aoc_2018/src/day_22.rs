@@ -1,25 +1,8 @@
+use common::search::{Search, SearchState};
 #[allow(unused_imports)]
 use helper::{
     print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, Point2D, RunOutput, Runner,
 };
-use std::collections::BTreeMap;
-
-fn add_seen(seen: &mut Vec<Vec<usize>>, coord: Point2D<u16>, time: usize) -> bool {
-    if seen.len() <= coord.y as usize {
-        seen.resize(coord.y as usize + 1, Vec::new());
-    }
-    if seen[coord.y as usize].len() <= coord.x as usize {
-        seen[coord.y as usize].resize(coord.x as usize + 1, usize::MAX);
-    }
-
-    let seen_time = seen[coord.y as usize][coord.x as usize];
-    if seen_time == usize::MAX {
-        seen[coord.y as usize][coord.x as usize] = time;
-        true
-    } else {
-        seen_time > time
-    }
-}
 
 fn erosion_level(
     geologic_index: &mut Vec<Vec<usize>>,
@@ -74,6 +57,81 @@ enum Terrain {
     Narrow,
 }
 
+impl Tool {
+    fn allowed(&self, terrain: Terrain) -> bool {
+        !matches!(
+            (terrain, self),
+            (Terrain::Rocky, Tool::None)
+                | (Terrain::Wet, Tool::Torch)
+                | (Terrain::Narrow, Tool::Gear)
+        )
+    }
+}
+
+struct Cave {
+    geologic_index: Vec<Vec<usize>>,
+    target: Point2D<u16>,
+    depth: usize,
+}
+
+impl Cave {
+    fn terrain(&mut self, coord: Point2D<u16>) -> Terrain {
+        match erosion_level(&mut self.geologic_index, coord, self.target, self.depth) % 3 {
+            0 => Terrain::Rocky,
+            1 => Terrain::Wet,
+            2 => Terrain::Narrow,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Climber {
+    coord: Point2D<u16>,
+    tool: Tool,
+}
+
+impl SearchState for Climber {
+    type Context = Cave;
+    type Key = (Tool, Point2D<u16>);
+
+    fn key(&self) -> Self::Key {
+        (self.tool, self.coord)
+    }
+
+    fn neighbors(&self, cave: &mut Cave, out: &mut Vec<(Self, usize)>) {
+        let terrain = cave.terrain(self.coord);
+        for tool in [Tool::None, Tool::Torch, Tool::Gear] {
+            if tool != self.tool && tool.allowed(terrain) {
+                out.push((Self { tool, ..*self }, 7));
+            }
+        }
+
+        for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+            let (Some(x), Some(y)) = (
+                self.coord.x.checked_add_signed(dx),
+                self.coord.y.checked_add_signed(dy),
+            ) else {
+                continue;
+            };
+            let coord = Point2D::new(x, y);
+            if self.tool.allowed(cave.terrain(coord)) {
+                out.push((Self { coord, ..*self }, 1));
+            }
+        }
+    }
+
+    fn is_goal(&self, cave: &Cave) -> bool {
+        self.coord == cave.target && self.tool == Tool::Torch
+    }
+
+    fn heuristic(&self, cave: &Cave) -> usize {
+        let distance = self.coord.x.abs_diff(cave.target.x) as usize
+            + self.coord.y.abs_diff(cave.target.y) as usize;
+        distance + if self.tool == Tool::Torch { 0 } else { 7 }
+    }
+}
+
 #[derive(Default)]
 pub struct Day22 {
     depth: usize,
@@ -134,114 +192,15 @@ impl Day22 {
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let mut geologic_index = Vec::new();
-        let mut seen_none: Vec<Vec<usize>> = Vec::new();
-        let mut seen_torch: Vec<Vec<usize>> = Vec::new();
-        let mut seen_gear: Vec<Vec<usize>> = Vec::new();
-
-        let mut queue: BTreeMap<usize, HashSet<(Tool, Point2D<u16>)>> = BTreeMap::new();
-
-        let mut tools = HashMap::default();
-        tools.insert((Terrain::Rocky, Terrain::Rocky, Tool::Gear), Tool::Gear);
-        tools.insert((Terrain::Rocky, Terrain::Rocky, Tool::Torch), Tool::Torch);
-        tools.insert((Terrain::Rocky, Terrain::Wet, Tool::Gear), Tool::Gear);
-        tools.insert((Terrain::Rocky, Terrain::Wet, Tool::Torch), Tool::Gear);
-        tools.insert((Terrain::Rocky, Terrain::Narrow, Tool::Gear), Tool::Torch);
-        tools.insert((Terrain::Rocky, Terrain::Narrow, Tool::Torch), Tool::Torch);
-
-        tools.insert((Terrain::Wet, Terrain::Wet, Tool::Gear), Tool::Gear);
-        tools.insert((Terrain::Wet, Terrain::Wet, Tool::None), Tool::None);
-        tools.insert((Terrain::Wet, Terrain::Rocky, Tool::Gear), Tool::Gear);
-        tools.insert((Terrain::Wet, Terrain::Rocky, Tool::None), Tool::Gear);
-        tools.insert((Terrain::Wet, Terrain::Narrow, Tool::Gear), Tool::None);
-        tools.insert((Terrain::Wet, Terrain::Narrow, Tool::None), Tool::None);
-
-        tools.insert((Terrain::Narrow, Terrain::Narrow, Tool::None), Tool::None);
-        tools.insert((Terrain::Narrow, Terrain::Narrow, Tool::Torch), Tool::Torch);
-        tools.insert((Terrain::Narrow, Terrain::Rocky, Tool::None), Tool::Torch);
-        tools.insert((Terrain::Narrow, Terrain::Rocky, Tool::Torch), Tool::Torch);
-        tools.insert((Terrain::Narrow, Terrain::Wet, Tool::None), Tool::None);
-        tools.insert((Terrain::Narrow, Terrain::Wet, Tool::Torch), Tool::None);
-
-        assert!(tools.len() == 6 * 3);
-
-        queue
-            .entry(0)
-            .or_default()
-            .insert((Tool::Torch, Point2D::new(0, 0)));
-
-        while let Some((time, time_queue)) = queue.pop_first() {
-            for (tool, coord) in time_queue {
-                if coord == self.target {
-                    if tool != Tool::Torch {
-                        queue
-                            .entry(time + 7)
-                            .or_default()
-                            .insert((Tool::Torch, coord));
-                        continue;
-                    }
-                    return Ok(time.into());
-                }
-
-                let seen = match tool {
-                    Tool::None => &mut seen_none,
-                    Tool::Gear => &mut seen_gear,
-                    Tool::Torch => &mut seen_torch,
-                };
-                if !add_seen(seen, coord, time) {
-                    continue;
-                }
-
-                let cur_terrain: Terrain =
-                    match erosion_level(&mut geologic_index, coord, self.target, self.depth) % 3 {
-                        0 => Terrain::Rocky,
-                        1 => Terrain::Wet,
-                        2 => Terrain::Narrow,
-                        _ => unreachable!(),
-                    };
-
-                for dir in 0..4 {
-                    let new_coord = match dir {
-                        0 => {
-                            if coord.y == 0 {
-                                continue;
-                            }
-                            Point2D::new(coord.x, coord.y - 1)
-                        }
-                        1 => {
-                            if coord.x == 0 {
-                                continue;
-                            }
-                            Point2D::new(coord.x - 1, coord.y)
-                        }
-                        2 => Point2D::new(coord.x, coord.y + 1),
-                        3 => Point2D::new(coord.x + 1, coord.y),
-                        _ => unreachable!(),
-                    };
-
-                    let new_terrain: Terrain = match erosion_level(
-                        &mut geologic_index,
-                        new_coord,
-                        self.target,
-                        self.depth,
-                    ) % 3
-                    {
-                        0 => Terrain::Rocky,
-                        1 => Terrain::Wet,
-                        2 => Terrain::Narrow,
-                        _ => unreachable!(),
-                    };
-
-                    let new_tool = tools.get(&(cur_terrain, new_terrain, tool)).unwrap();
-                    let new_tool = *new_tool;
-                    let new_time = if new_tool == tool { time + 1 } else { time + 8 };
-                    queue
-                        .entry(new_time)
-                        .or_default()
-                        .insert((new_tool, new_coord));
-                }
-            }
-        }
-        Err(Error::Unsolved)
+        let mut cave = Cave {
+            geologic_index: Vec::new(),
+            target: self.target,
+            depth: self.depth,
+        };
+        let start = Climber {
+            coord: Point2D::new(0, 0),
+            tool: Tool::Torch,
+        };
+        Ok(Search::new(&mut cave).astar(start).cost()?.into())
     }
 }
@@ -0,0 +1,253 @@
+use helper::{Error, Lines};
+use std::{fmt::Write, str::FromStr};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum OpCode {
+    AddR,
+    AddI,
+    MulR,
+    MulI,
+    BanR,
+    BanI,
+    BorR,
+    BorI,
+    SetR,
+    SetI,
+    GtIR,
+    GtRI,
+    GtRR,
+    EqIR,
+    EqRI,
+    EqRR,
+}
+
+impl OpCode {
+    pub(crate) const ALL: [OpCode; 16] = [
+        OpCode::AddR,
+        OpCode::AddI,
+        OpCode::MulR,
+        OpCode::MulI,
+        OpCode::BanR,
+        OpCode::BanI,
+        OpCode::BorR,
+        OpCode::BorI,
+        OpCode::SetR,
+        OpCode::SetI,
+        OpCode::GtIR,
+        OpCode::GtRI,
+        OpCode::GtRR,
+        OpCode::EqIR,
+        OpCode::EqRI,
+        OpCode::EqRR,
+    ];
+
+    pub(crate) fn mnemonic(self) -> &'static str {
+        match self {
+            OpCode::AddR => "addr",
+            OpCode::AddI => "addi",
+            OpCode::MulR => "mulr",
+            OpCode::MulI => "muli",
+            OpCode::BanR => "banr",
+            OpCode::BanI => "bani",
+            OpCode::BorR => "borr",
+            OpCode::BorI => "bori",
+            OpCode::SetR => "setr",
+            OpCode::SetI => "seti",
+            OpCode::GtIR => "gtir",
+            OpCode::GtRI => "gtri",
+            OpCode::GtRR => "gtrr",
+            OpCode::EqIR => "eqir",
+            OpCode::EqRI => "eqri",
+            OpCode::EqRR => "eqrr",
+        }
+    }
+
+    // Which of the a and b operands name a register rather than an immediate.
+    fn reg_operands(self) -> (bool, bool) {
+        match self {
+            OpCode::AddR | OpCode::MulR | OpCode::BanR | OpCode::BorR => (true, true),
+            OpCode::AddI | OpCode::MulI | OpCode::BanI | OpCode::BorI => (true, false),
+            OpCode::SetR => (true, false),
+            OpCode::SetI => (false, false),
+            OpCode::GtIR | OpCode::EqIR => (false, true),
+            OpCode::GtRI | OpCode::EqRI => (true, false),
+            OpCode::GtRR | OpCode::EqRR => (true, true),
+        }
+    }
+
+    pub(crate) fn execute(self, registers: &mut [usize], a: usize, b: usize, c: usize) {
+        registers[c] = match self {
+            OpCode::AddR => registers[a] + registers[b],
+            OpCode::AddI => registers[a] + b,
+            OpCode::MulR => registers[a] * registers[b],
+            OpCode::MulI => registers[a] * b,
+            OpCode::BanR => registers[a] & registers[b],
+            OpCode::BanI => registers[a] & b,
+            OpCode::BorR => registers[a] | registers[b],
+            OpCode::BorI => registers[a] | b,
+            OpCode::SetR => registers[a],
+            OpCode::SetI => a,
+            OpCode::GtIR => (a > registers[b]) as usize,
+            OpCode::GtRI => (registers[a] > b) as usize,
+            OpCode::GtRR => (registers[a] > registers[b]) as usize,
+            OpCode::EqIR => (a == registers[b]) as usize,
+            OpCode::EqRI => (registers[a] == b) as usize,
+            OpCode::EqRR => (registers[a] == registers[b]) as usize,
+        };
+    }
+}
+
+impl FromStr for OpCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OpCode::ALL
+            .iter()
+            .find(|op_code| op_code.mnemonic() == s)
+            .copied()
+            .ok_or_else(|| Error::InvalidInput(format!("Unknown OpCode: {s:?}")))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Instruction {
+    pub(crate) opcode: OpCode,
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    pub(crate) c: usize,
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        if parts.len() != 4 {
+            return Err(Error::InvalidInput(s.into()));
+        }
+        Ok(Self {
+            opcode: parts[0].parse()?,
+            a: parts[1].parse()?,
+            b: parts[2].parse()?,
+            c: parts[3].parse()?,
+        })
+    }
+}
+
+pub(crate) enum Hook {
+    Continue,
+    Stop,
+}
+
+#[derive(Default, Clone)]
+pub struct Cpu {
+    pub(crate) registers: [usize; 6],
+    pub(crate) ip: Option<usize>,
+    pub(crate) pc: usize,
+    pub(crate) program: Vec<Instruction>,
+}
+
+impl Cpu {
+    pub fn load(&mut self, lines: Lines) -> Result<(), Error> {
+        self.program.clear();
+        for line in lines.iter() {
+            if let Some(ip) = line.strip_prefix("#ip ") {
+                self.ip = Some(ip.parse()?);
+            } else if !line.is_empty() {
+                self.program.push(line.parse()?);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn step(&mut self) -> bool {
+        let Some(inst) = self.program.get(self.pc).copied() else {
+            return false;
+        };
+        if let Some(ip) = self.ip {
+            self.registers[ip] = self.pc;
+        }
+        inst.opcode
+            .execute(&mut self.registers, inst.a, inst.b, inst.c);
+        if let Some(ip) = self.ip {
+            self.pc = self.registers[ip];
+        }
+        self.pc += 1;
+        true
+    }
+
+    pub(crate) fn run(&mut self) {
+        while self.step() {}
+    }
+
+    // Calls `hook` before every instruction.  The hook may inspect and rewrite the registers to
+    // skip over a hot loop, or stop execution early.
+    pub(crate) fn run_with_hooks<F>(&mut self, mut hook: F)
+    where
+        F: FnMut(&mut Self) -> Hook,
+    {
+        while self.pc < self.program.len() {
+            if let Hook::Stop = hook(self) {
+                break;
+            }
+            if !self.step() {
+                break;
+            }
+        }
+    }
+
+    // Renders the program as pseudo-code.  Reads of the bound instruction pointer are replaced
+    // by the current address and writes to it are shown as jumps.
+    pub fn disassemble(&self) -> String {
+        let mut out = String::new();
+        for (pc, inst) in self.program.iter().enumerate() {
+            let reg = |r: usize| {
+                if Some(r) == self.ip {
+                    pc.to_string()
+                } else {
+                    format!("r{r}")
+                }
+            };
+            let (a_reg, b_reg) = inst.opcode.reg_operands();
+            let a = if a_reg {
+                reg(inst.a)
+            } else {
+                inst.a.to_string()
+            };
+            let b = if b_reg {
+                reg(inst.b)
+            } else {
+                inst.b.to_string()
+            };
+            let expr = match inst.opcode {
+                OpCode::AddR | OpCode::AddI => format!("{a} + {b}"),
+                OpCode::MulR | OpCode::MulI => format!("{a} * {b}"),
+                OpCode::BanR | OpCode::BanI => format!("{a} & {b}"),
+                OpCode::BorR | OpCode::BorI => format!("{a} | {b}"),
+                OpCode::SetR | OpCode::SetI => a,
+                OpCode::GtIR | OpCode::GtRI | OpCode::GtRR => format!("{a} > {b}"),
+                OpCode::EqIR | OpCode::EqRI | OpCode::EqRR => format!("{a} == {b}"),
+            };
+
+            let _ = write!(out, "{pc:3}: ");
+            if Some(inst.c) == self.ip {
+                let target = match inst.opcode {
+                    OpCode::SetI => Some(inst.a + 1),
+                    OpCode::AddI if Some(inst.a) == self.ip => Some(pc + inst.b + 1),
+                    _ => None,
+                };
+                match target {
+                    Some(target) => {
+                        let _ = writeln!(out, "goto {target}");
+                    }
+                    None => {
+                        let _ = writeln!(out, "goto ({expr}) + 1");
+                    }
+                }
+            } else {
+                let _ = writeln!(out, "{} = {expr}", reg(inst.c));
+            }
+        }
+        out
+    }
+}
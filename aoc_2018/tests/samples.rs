@@ -0,0 +1 @@
+common::sample_tests!(aoc_2018::register, 2018);
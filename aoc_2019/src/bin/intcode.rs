@@ -0,0 +1,246 @@
+use aoc_2019::intcode::{IntCode, State};
+use helper::{Error, Lines, LinesOpt};
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+const USAGE: &str = "Usage: intcode [--ascii] [--replay <transcript>] [--trace <file>] <program>";
+
+const HELP: &str = "Lines not starting with '/' are sent to the program as input.
+  /save <name>          snapshot the machine
+  /restore <name>       restore a snapshot
+  /snapshots            list snapshots
+  /dump [start] [len]   dump memory
+  /disasm               annotated listing of the current memory
+  /trace <file>         start recording executed instructions, written to file on /trace off
+  /trace off            stop recording and write the trace
+  /replay <file>        feed each line of a transcript as if it was typed
+  /transcript <file>    write every line entered so far to a file
+  /quit                 exit";
+
+// How deep transcripts may /replay other transcripts, so one replaying itself stops
+const MAX_REPLAY_DEPTH: usize = 16;
+
+struct Repl {
+    intcode: IntCode<i64>,
+    ascii: bool,
+    snapshots: HashMap<String, IntCode<i64>>,
+    transcript: Vec<String>,
+    trace: Option<String>,
+    replay_depth: usize,
+}
+
+impl Repl {
+    // Runs the program until it needs input or stops, printing everything it outputs.
+    fn run(&mut self) {
+        let mut stdout = std::io::stdout().lock();
+        loop {
+            match self.intcode.run() {
+                State::HasOutput(v) => {
+                    let _ = if self.ascii && (0..128).contains(&v) {
+                        write!(stdout, "{}", v as u8 as char)
+                    } else {
+                        writeln!(stdout, "{v}")
+                    };
+                }
+                State::WaitingForInput(..) => return,
+                State::Stopped => {
+                    let _ = writeln!(stdout, "[program stopped]");
+                    return;
+                }
+                State::Fault(fault) => {
+                    let _ = writeln!(stdout, "[program faulted: {fault}]");
+                    return;
+                }
+                State::Running => unreachable!(),
+            }
+        }
+    }
+
+    fn input(&mut self, line: &str) -> Result<(), String> {
+        if self.intcode.is_stopped() {
+            return Err("Program has stopped, /restore a snapshot or /quit".into());
+        }
+        if self.ascii {
+            self.intcode.append_ascii(line);
+            self.intcode.append_ascii("\n");
+        } else {
+            for num in line.split([',', ' ']).filter(|s| !s.is_empty()) {
+                let num = num.parse().map_err(|_| format!("Not a number: {num:?}"))?;
+                self.intcode.input.push_back(num);
+            }
+        }
+        Ok(())
+    }
+
+    fn dump(&self, start: usize, len: usize) {
+        let end = start.saturating_add(len).min(self.intcode.len());
+        for (i, row) in self.intcode[start.min(end)..end].chunks(8).enumerate() {
+            print!("{:6}:", start + i * 8);
+            for v in row {
+                print!(" {v:>8}");
+            }
+            println!();
+        }
+    }
+
+    fn stop_trace(&mut self) -> Result<(), String> {
+        if let Some(file) = self.trace.take() {
+            self.intcode
+                .write_trace(&file)
+                .map_err(|e| format!("{e:?}"))?;
+            self.intcode.set_trace(false);
+        }
+        Ok(())
+    }
+
+    fn command(&mut self, line: &str) -> Result<bool, String> {
+        let mut parts = line.split_whitespace();
+        let command = parts.next().unwrap_or("");
+        let arg = parts.next();
+        let name = || arg.ok_or(format!("{command} needs an argument"));
+        match command {
+            "/save" => {
+                self.snapshots.insert(name()?.into(), self.intcode.clone());
+            }
+            "/restore" => {
+                let name = name()?;
+                self.intcode = self
+                    .snapshots
+                    .get(name)
+                    .ok_or(format!("No snapshot named {name:?}"))?
+                    .clone();
+            }
+            "/snapshots" => {
+                for name in self.snapshots.keys() {
+                    println!("{name}");
+                }
+            }
+            "/dump" => {
+                let parse = |s: Option<&str>, default| {
+                    s.map(|s| s.parse().map_err(|_| format!("Not a number: {s:?}")))
+                        .unwrap_or(Ok(default))
+                };
+                let start = parse(arg, 0)?;
+                let len = parse(parts.next(), self.intcode.len())?;
+                self.dump(start, len);
+            }
+            "/disasm" => print!("{}", self.intcode.disassemble()),
+            "/trace" => match name()? {
+                "off" => self.stop_trace()?,
+                file => {
+                    self.stop_trace()?;
+                    self.intcode.set_trace(true);
+                    self.trace = Some(file.into());
+                }
+            },
+            "/replay" => {
+                if self.replay_depth >= MAX_REPLAY_DEPTH {
+                    return Err(format!("Replays nested over {MAX_REPLAY_DEPTH} deep"));
+                }
+                let file = std::fs::read_to_string(name()?).map_err(|e| e.to_string())?;
+                self.replay_depth += 1;
+                let replayed = self.replay(&file);
+                self.replay_depth -= 1;
+                return replayed;
+            }
+            "/transcript" => {
+                let mut file = self.transcript.join("\n");
+                file.push('\n');
+                std::fs::write(name()?, file).map_err(|e| e.to_string())?;
+            }
+            "/quit" => {
+                self.stop_trace()?;
+                return Ok(false);
+            }
+            "/help" => println!("{HELP}"),
+            _ => return Err(format!("Unknown command {command:?}, try /help")),
+        }
+        Ok(true)
+    }
+
+    fn replay(&mut self, file: &str) -> Result<bool, String> {
+        for line in file.lines() {
+            println!("> {line}");
+            if !self.line(line)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Handles one line of user input, returning false once the session should end.
+    fn line(&mut self, line: &str) -> Result<bool, String> {
+        if line.starts_with('/') {
+            return self.command(line);
+        }
+        self.input(line)?;
+        self.transcript.push(line.into());
+        self.run();
+        Ok(true)
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let mut ascii = false;
+    let mut replay = None;
+    let mut trace = None;
+    let mut program = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ascii" => ascii = true,
+            "--replay" => replay = args.next(),
+            "--trace" => trace = args.next(),
+            _ if program.is_none() => program = Some(arg),
+            _ => return Err(Error::Runner(USAGE.into())),
+        }
+    }
+    let Some(program) = program else {
+        return Err(Error::Runner(USAGE.into()));
+    };
+
+    let mut repl = Repl {
+        intcode: IntCode::default(),
+        ascii,
+        snapshots: HashMap::new(),
+        transcript: Vec::new(),
+        trace: None,
+        replay_depth: 0,
+    };
+    let file = std::fs::read(&program).map_err(|e| Error::Runner(format!("{program}: {e}")))?;
+    repl.intcode
+        .load(Lines::from_bufread(&file[..], LinesOpt::RAW)?)?;
+    if let Some(trace) = trace {
+        repl.intcode.set_trace(true);
+        repl.trace = Some(trace);
+    }
+    repl.run();
+
+    if let Some(replay) = replay {
+        match repl.command(&format!("/replay {replay}")) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line.map_err(|e| Error::Runner(e.to_string()))?;
+        match repl.line(&line) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+
+    repl.stop_trace().map_err(Error::Runner)
+}
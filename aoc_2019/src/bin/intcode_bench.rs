@@ -0,0 +1,104 @@
+use aoc_2019::intcode::{IntCode, State};
+use helper::{Error, Lines, LinesOpt};
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: intcode_bench [--iterations <n>] <day>=<input> ...
+Times each day's program with instructions decoded on every step and with pre-decoded
+instructions, e.g.
+  intcode_bench 9=input/09.txt 17=input/17.txt 19=input/19.txt 25=input/25.txt";
+
+// Runs until the program stops or wants input it has not been given
+fn run(intcode: &mut IntCode<i64>) -> Result<(), Error> {
+    loop {
+        match intcode.run() {
+            State::HasOutput(_) => {}
+            State::Stopped | State::WaitingForInput(..) => return Ok(()),
+            state => return Err(state.into()),
+        }
+    }
+}
+
+// Feeds a program the same kind of work its day gives it
+fn drive(day: usize, program: &IntCode<i64>) -> Result<(), Error> {
+    match day {
+        // The BOOST self test and then the full run
+        9 => {
+            for mode in [1, 2] {
+                let mut intcode = program.clone();
+                intcode.input.push_back(mode);
+                run(&mut intcode)?;
+            }
+        }
+        // Drawing the scaffold map
+        17 => run(&mut program.clone())?,
+        // Scanning the tractor beam one point at a time, each probe a copy of a program that
+        // has already been decoded
+        19 => {
+            let mut template = program.clone();
+            template.warm_predecode();
+            for y in 0..50 {
+                for x in 0..50 {
+                    let mut intcode = template.clone();
+                    intcode.input.extend([x, y]);
+                    run(&mut intcode)?;
+                }
+            }
+        }
+        // Wandering the ship's first room
+        25 => {
+            let mut intcode = program.clone();
+            for _ in 0..100 {
+                intcode.append_ascii("inv\n");
+            }
+            run(&mut intcode)?;
+        }
+        _ => return Err(Error::Runner(format!("No benchmark for 2019 day {day}"))),
+    }
+    Ok(())
+}
+
+fn time_day(day: usize, program: &IntCode<i64>, iterations: u32) -> Result<Duration, Error> {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        drive(day, program)?;
+    }
+    Ok(start.elapsed() / iterations)
+}
+
+fn main() -> Result<(), Error> {
+    let mut iterations = 5;
+    let mut days = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--iterations" {
+            iterations = args
+                .next()
+                .and_then(|n| n.parse().ok())
+                .filter(|n| *n > 0)
+                .ok_or_else(|| Error::Runner(USAGE.into()))?;
+        } else if let Some((day, input)) = arg.split_once('=') {
+            let day: usize = day.parse().map_err(|_| Error::Runner(USAGE.into()))?;
+            let input = std::fs::read(input).map_err(|e| Error::Runner(format!("{input}: {e}")))?;
+            days.push((day, input));
+        } else {
+            return Err(Error::Runner(USAGE.into()));
+        }
+    }
+    if days.is_empty() {
+        return Err(Error::Runner(USAGE.into()));
+    }
+
+    println!("day  decode each step   pre-decoded  speedup");
+    for (day, input) in days {
+        let mut program = IntCode::default();
+        program.load(Lines::from_bufread(&input[..], LinesOpt::RAW)?)?;
+        let slow = time_day(day, &program, iterations)?;
+        program.set_predecode(true);
+        let fast = time_day(day, &program, iterations)?;
+        println!(
+            "{day:3}  {slow:>16.3?}  {fast:>12.3?}  {:6.2}x",
+            slow.as_secs_f64() / fast.as_secs_f64()
+        );
+    }
+    Ok(())
+}
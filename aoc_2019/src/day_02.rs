@@ -1,4 +1,4 @@
-use crate::intcode::IntCode;
+use crate::intcode::{IntCode, State};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -32,7 +32,9 @@ impl Day02 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         self.intcode[1] = 12;
         self.intcode[2] = 2;
-        self.intcode.run();
+        if let State::Fault(fault) = self.intcode.run() {
+            return Err(fault.into());
+        }
         Ok(self.intcode[0].into())
     }
 
@@ -45,7 +47,9 @@ impl Day02 {
                 let mut intcode = self.intcode.clone();
                 intcode[1] = a;
                 intcode[2] = b;
-                intcode.run();
+                if let State::Fault(fault) = intcode.run() {
+                    return Err(fault.into());
+                }
                 if intcode[0] == 19690720 {
                     return Ok(((a * 100) + b).into());
                 }
@@ -36,7 +36,7 @@ impl Day05 {
             match self.intcode.run() {
                 State::HasOutput(v) => output = v,
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
         Ok(output.into())
@@ -49,7 +49,7 @@ impl Day05 {
             match self.intcode.run() {
                 State::HasOutput(v) => output = v,
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
         Ok(output.into())
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
+use common::dot::{Dot, GraphDump};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
 pub struct Day06 {
+    names: Vec<String>,
     orbits: Vec<Vec<usize>>,
     you: usize,
     san: usize,
@@ -12,6 +14,7 @@ pub struct Day06 {
 impl Day06 {
     pub fn new() -> Self {
         Self {
+            names: Vec::new(),
             orbits: Vec::new(),
             you: usize::MAX,
             san: usize::MAX,
@@ -19,6 +22,19 @@ impl Day06 {
     }
 }
 
+impl GraphDump for Day06 {
+    fn dot(&self) -> Dot {
+        let mut dot = Dot::digraph("orbits");
+        for (name, orbits) in self.names.iter().zip(self.orbits.iter()) {
+            match orbits.first() {
+                Some(around) => dot.edge(&self.names[*around], name, &[]),
+                None => dot.node(name, &[]),
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day06 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
@@ -57,6 +73,11 @@ impl Runner for Day06 {
             }
             self.orbits.push(orbits);
         }
+
+        self.names = vec![String::new(); names.len()];
+        for (name, idx) in names {
+            self.names[idx] = name.into();
+        }
         Ok(())
     }
 
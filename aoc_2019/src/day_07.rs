@@ -1,86 +1,15 @@
-use crate::intcode::{IntCode, State, Word};
+use crate::intcode::{Event, IntCode, Network, Routing};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
-use std::collections::VecDeque;
-
-#[derive(Default, Clone)]
-pub(crate) struct Amplifiers<T> {
-    amps: Vec<IntCode<T>>,
-    pub(crate) output: Option<T>,
-    pub(crate) master_output: usize,
-    pub(crate) outputs: Vec<Vec<usize>>,
-}
-
-impl<T> std::ops::Deref for Amplifiers<T> {
-    type Target = [IntCode<T>];
-
-    fn deref(&self) -> &Self::Target {
-        &self.amps
-    }
-}
-
-impl<T> std::ops::DerefMut for Amplifiers<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.amps
-    }
-}
-impl<T> Amplifiers<T>
-where
-    T: Word + Default,
-    Error: From<<T as std::str::FromStr>::Err>,
-{
-    pub(crate) fn load(&mut self, lines: Lines, n: usize) -> Result<(), Error> {
-        let mut intcode = IntCode::default();
-        intcode.load(lines)?;
-
-        self.amps = vec![intcode.clone(); n];
-        self.outputs = (0..n)
-            .map(|i| if i == n - 1 { Vec::new() } else { vec![i + 1] })
-            .collect();
-
-        self.master_output = n - 1;
-        self.output = None;
-        Ok(())
-    }
-
-    pub(crate) fn run(&mut self) {
-        let mut runnable: VecDeque<usize> = self.amps.iter().enumerate().map(|(i, _)| i).collect();
-        let mut waiting: Vec<usize> = Vec::new();
-        while let Some(idx) = runnable.pop_front() {
-            let state = self.amps[idx].run();
-            match state {
-                State::Stopped => {}
-                State::WaitingForInput(..) => {
-                    waiting.push(idx);
-                }
-                State::HasOutput(v) => {
-                    if self.master_output == idx {
-                        self.output = Some(v);
-                    }
-                    for idx in self.outputs[idx].iter() {
-                        self.amps[*idx].input.push_back(v);
-                        if let Some(pos) = waiting.iter().position(|v| v == idx) {
-                            // Add waiting chip back to the running queue
-                            runnable.push_back(*idx);
-                            waiting.swap_remove(pos);
-                        }
-                    }
-                    runnable.push_back(idx);
-                }
-                State::Running => unreachable!(),
-            }
-        }
-    }
-}
 
 pub struct Day07 {
-    amps: Amplifiers<i32>,
+    intcode: IntCode<i32>,
 }
 
 impl Day07 {
     pub fn new() -> Self {
         Self {
-            amps: Amplifiers::default(),
+            intcode: IntCode::default(),
         }
     }
 
@@ -104,17 +33,32 @@ impl Day07 {
         }
     }
 
-    pub fn get_best(&self, low: i32) -> i32 {
+    fn thrust(&self, phases: &[i32], routing: Routing<i32>) -> Result<i32, Error> {
+        let mut amps = Network::new(&self.intcode, phases.len(), routing);
+        for (i, v) in phases.iter().enumerate() {
+            amps.send(i, &[*v]);
+        }
+        amps.send(0, &[0]);
+
+        let mut thrust = None;
+        loop {
+            match amps.next_event()? {
+                Event::Output(v) => thrust = Some(v),
+                Event::Halted => break,
+                Event::Idle => return Err(Error::Runner("Amplifiers deadlocked".into())),
+                Event::Nat(..) | Event::Wake(..) => unreachable!(),
+            }
+        }
+        thrust.ok_or(Error::Unsolved)
+    }
+
+    pub fn get_best(&self, low: i32, routing: Routing<i32>) -> Result<i32, Error> {
         let mut numbers = (low..low + 5).collect();
-        let mut best = 0;
-        Self::generate(5, &mut numbers, &mut |vals| {
-            let mut amps = self.amps.clone();
-            for (i, v) in vals.iter().enumerate() {
-                amps[i].input.push_front(*v);
+        let mut best = Ok(0);
+        Self::generate(5, &mut numbers, &mut |phases| {
+            if let Ok(current) = best {
+                best = self.thrust(phases, routing).map(|v| current.max(v));
             }
-            amps[0].input.push_back(0);
-            amps.run();
-            best = best.max(amps.output.unwrap());
         });
         best
     }
@@ -122,7 +66,7 @@ impl Day07 {
 
 impl Runner for Day07 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
-        self.amps.load(Lines::from_bufread(file, LinesOpt::RAW)?, 5)
+        self.intcode.load(Lines::from_bufread(file, LinesOpt::RAW)?)
     }
 
     fn run_part(&mut self, part: u8) -> Result<RunOutput, Error> {
@@ -136,11 +80,10 @@ impl Runner for Day07 {
 
 impl Day07 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.get_best(0).into())
+        Ok(self.get_best(0, Routing::Pipeline)?.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        self.amps.outputs[4].push(0);
-        Ok(self.get_best(5).into())
+        Ok(self.get_best(5, Routing::Ring)?.into())
     }
 }
@@ -90,6 +90,6 @@ impl Day08 {
             }
             ans.push('\n');
         }
-        Ok(ans.into())
+        Ok(common::ocr::recognize(&ans)?.into())
     }
 }
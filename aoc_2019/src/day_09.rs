@@ -16,6 +16,7 @@ impl Day09 {
 
 impl Runner for Day09 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
+        self.intcode.set_predecode(true);
         self.intcode.load(Lines::from_bufread(file, LinesOpt::RAW)?)
     }
 
@@ -36,7 +37,7 @@ impl Day09 {
             match self.intcode.run() {
                 State::HasOutput(v) => output = v,
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
         Ok((output as usize).into())
@@ -49,7 +50,7 @@ impl Day09 {
             match self.intcode.run() {
                 State::HasOutput(v) => output = v,
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
         Ok((output as usize).into())
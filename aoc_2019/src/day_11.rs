@@ -16,7 +16,7 @@ impl Day11 {
         }
     }
 
-    fn run(&mut self) {
+    fn run(&mut self) -> Result<(), Error> {
         let mut pos = (0, 0);
         let mut dir = 0;
         let deltas = [(0, -1), (1, 0), (0, 1), (-1, 0)];
@@ -39,9 +39,10 @@ impl Day11 {
                     }
                 }
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
+        Ok(())
     }
 
     fn output(&self) -> String {
@@ -91,13 +92,13 @@ impl Runner for Day11 {
 
 impl Day11 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        self.run();
+        self.run()?;
         Ok(self.painted.len().into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
         self.painted.insert((0, 0), true);
-        self.run();
-        Ok(self.output().into())
+        self.run()?;
+        Ok(common::ocr::recognize(&self.output())?.into())
     }
 }
@@ -1,3 +1,4 @@
+use common::cycle;
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -59,6 +60,31 @@ impl Day12 {
     }
 }
 
+// The position and velocity of every moon along one axis, which move independently of the others
+type Axis = [(isize, isize); 4];
+
+fn step_axis(moons: &mut Axis) {
+    use std::cmp::Ordering;
+    for i in 0..moons.len() {
+        for j in i + 1..moons.len() {
+            match moons[i].0.cmp(&moons[j].0) {
+                Ordering::Less => {
+                    moons[i].1 += 1;
+                    moons[j].1 -= 1;
+                }
+                Ordering::Greater => {
+                    moons[i].1 -= 1;
+                    moons[j].1 += 1;
+                }
+                Ordering::Equal => {}
+            }
+        }
+    }
+    for moon in moons.iter_mut() {
+        moon.0 += moon.1;
+    }
+}
+
 impl Runner for Day12 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
@@ -110,49 +136,14 @@ impl Day12 {
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let initial = self.moons;
-        let mut x = None;
-        let mut y = None;
-        let mut z = None;
-        let mut found = 0;
-        for i in 1.. {
-            self.step();
-
-            macro_rules! check_axis {
-                ($axis:ident) => {
-                    if $axis.is_none() {
-                        let matches = self
-                            .moons
-                            .iter()
-                            .zip(initial.iter())
-                            .map(|(m, i)| {
-                                if m.pos.$axis == i.pos.$axis && m.vel.$axis == i.vel.$axis {
-                                    1
-                                } else {
-                                    0
-                                }
-                            })
-                            .sum::<usize>();
-                        if matches == 4 {
-                            $axis = Some(i);
-                            found += 1;
-                        }
-                    }
-                };
-            }
-
-            check_axis!(x);
-            check_axis!(y);
-            check_axis!(z);
-
-            if found == 3 {
-                break;
-            }
-        }
-
-        let x = x.unwrap();
-        let y = y.unwrap();
-        let z = z.unwrap();
+        // Each axis repeats on its own, and the whole system once they all line up
+        let period = |axis: fn(&Tripple) -> isize| {
+            let moons = self.moons.map(|moon| (axis(&moon.pos), axis(&moon.vel)));
+            cycle::brent(moons, step_axis).map(|cycle| cycle.len)
+        };
+        let x = period(|t| t.x)?;
+        let y = period(|t| t.y)?;
+        let z = period(|t| t.z)?;
         println!("{x} {y} {z}");
         Ok(helper::lcm(x, helper::lcm(y, z)).into())
     }
@@ -48,7 +48,7 @@ impl Day13 {
                     }
                 }
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
         Ok(blocks.len().into())
@@ -80,7 +80,7 @@ impl Day13 {
                     Ordering::Equal => self.intcode.input.push_front(0),
                     Ordering::Greater => self.intcode.input.push_front(-1),
                 },
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
         Ok(score.into())
@@ -39,7 +39,7 @@ impl Day15 {
         }
     }
 
-    pub fn map(&mut self) {
+    pub fn map(&mut self) -> Result<(), Error> {
         let mut pos = (0, 0);
         let mut missing = BTreeSet::new();
         missing.insert((0, -1));
@@ -80,9 +80,10 @@ impl Day15 {
                         .extend(path.iter().map(|d| *d as isize + 1));
                 }
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
+        Ok(())
     }
 
     fn find_shortest_path(&self, from: (isize, isize), to: &BTreeSet<(isize, isize)>) -> Vec<Dir> {
@@ -177,13 +178,13 @@ impl Runner for Day15 {
 
 impl Day15 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        self.map();
+        self.map()?;
         let path = self.find_shortest_path((0, 0), &[self.oxygen].iter().copied().collect());
         Ok(path.len().into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        self.map();
+        self.map()?;
         Ok(self.find_longest_path(self.oxygen).len().into())
     }
 }
@@ -17,7 +17,7 @@ impl Day17 {
         }
     }
 
-    fn get_map(&self) -> (HashSet<Pos>, Pos) {
+    fn get_map(&self) -> Result<(HashSet<Pos>, Pos), Error> {
         let mut y = 0;
         let mut x = 0;
         let mut map = HashSet::new();
@@ -43,7 +43,7 @@ impl Day17 {
                     x => unreachable!("Unexpected map char {x:?}"),
                 },
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
 
@@ -61,11 +61,11 @@ impl Day17 {
         //     println!();
         // }
 
-        (map, start)
+        Ok((map, start))
     }
 
-    fn get_commands(&self) -> Vec<Command> {
-        let (map, start) = self.get_map();
+    fn get_commands(&self) -> Result<Vec<Command>, Error> {
+        let (map, start) = self.get_map()?;
 
         let mut dir = Dir::North;
         let mut cur = start;
@@ -76,7 +76,7 @@ impl Day17 {
             cur = c;
         }
 
-        commands
+        Ok(commands)
     }
 }
 
@@ -293,6 +293,7 @@ fn try_encode(
 
 impl Runner for Day17 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
+        self.intcode.set_predecode(true);
         self.intcode.load(Lines::from_bufread(file, LinesOpt::RAW)?)
     }
 
@@ -307,7 +308,7 @@ impl Runner for Day17 {
 
 impl Day17 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        let (map, _) = self.get_map();
+        let (map, _) = self.get_map()?;
         let mut alignment = 0;
         for pos in map.iter() {
             if map.contains(&Pos(pos.0 - 1, pos.1))
@@ -322,7 +323,7 @@ impl Day17 {
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let commands = self.get_commands();
+        let commands = self.get_commands()?;
 
         let mut command_sets = Vec::new();
         for i in 0..commands.len() {
@@ -352,7 +353,7 @@ impl Day17 {
                             match self.intcode.run() {
                                 State::Stopped => return Ok(dust.into()),
                                 State::HasOutput(v) => dust = v,
-                                x => unreachable!("Unexpected state: {x:?}"),
+                                state => return Err(state.into()),
                             }
                         }
                     }
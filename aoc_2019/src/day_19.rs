@@ -16,23 +16,23 @@ impl Day19 {
         }
     }
 
-    fn scan_sector(&mut self, x: isize, y: isize) -> bool {
+    fn scan_sector(&mut self, x: isize, y: isize) -> Result<bool, Error> {
         let mut intcode = self.intcode.clone();
         intcode.input.push_back(x);
         intcode.input.push_back(y);
         match intcode.run() {
-            State::HasOutput(v) => v == 1,
-            x => unreachable!("Unexpected state: {x:?}"),
+            State::HasOutput(v) => Ok(v == 1),
+            state => Err(state.into()),
         }
     }
 
-    fn run_scan(&mut self, is_done: impl Fn(&[Range<isize>]) -> bool) {
+    fn run_scan(&mut self, is_done: impl Fn(&[Range<isize>]) -> bool) -> Result<(), Error> {
         let mut last_left = 0;
         let mut left = 0;
         let mut right = 0;
         const MAX_DIFF: isize = 10;
         for y in 0.. {
-            while !self.scan_sector(left, y) && left - last_left < MAX_DIFF {
+            while !self.scan_sector(left, y)? && left - last_left < MAX_DIFF {
                 left += 1;
             }
             if left - last_left == MAX_DIFF {
@@ -43,7 +43,7 @@ impl Day19 {
             if left >= right {
                 right = left + 1;
             }
-            while self.scan_sector(right, y) {
+            while self.scan_sector(right, y)? {
                 right += 1;
             }
             self.rows.push(left..right);
@@ -52,6 +52,7 @@ impl Day19 {
             }
             last_left = left;
         }
+        Ok(())
     }
 }
 
@@ -72,7 +73,7 @@ impl Runner for Day19 {
 impl Day19 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         const MAX: isize = 50;
-        self.run_scan(|rows| rows.len() == MAX as usize);
+        self.run_scan(|rows| rows.len() == MAX as usize)?;
         Ok(self
             .rows
             .iter()
@@ -91,7 +92,7 @@ impl Day19 {
                 let top_row = &rows[rows.len() - SQUARE as usize];
                 last_row.end - last_row.start >= SQUARE && top_row.end - last_row.start >= SQUARE
             }
-        });
+        })?;
         let last_row = &self.rows[self.rows.len() - 1];
         let x = last_row.start;
         let y = self.rows.len() as isize - SQUARE;
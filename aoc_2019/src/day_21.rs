@@ -13,7 +13,7 @@ impl Day21 {
         }
     }
 
-    pub fn run(&mut self, commands: &[&str]) -> isize {
+    pub fn run(&mut self, commands: &[&str]) -> Result<isize, Error> {
         for cmd in commands.iter() {
             self.intcode.input.extend(cmd.chars().map(|c| c as isize));
             self.intcode.input.push_back(b'\n' as isize);
@@ -24,11 +24,11 @@ impl Day21 {
             match self.intcode.run() {
                 State::HasOutput(v) => last_damage = v,
                 State::Stopped => break,
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
 
-        last_damage
+        Ok(last_damage)
     }
 }
 
@@ -51,7 +51,7 @@ impl Day21 {
         Ok(self
             .run(&[
                 "NOT A T", "NOT B J", "OR T J", "NOT C T", "OR T J", "AND D J", "WALK",
-            ])
+            ])?
             .into())
     }
 
@@ -60,7 +60,7 @@ impl Day21 {
             .run(&[
                 "NOT A T", "NOT B J", "OR T J", "NOT C T", "OR T J", "AND D J", "NOT E T",
                 "NOT T T", "OR H T", "AND T J", "RUN",
-            ])
+            ])?
             .into())
     }
 }
@@ -1,79 +1,15 @@
-use crate::intcode::{IntCode, State};
+use crate::intcode::{Event, IntCode, Network, Routing};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
-struct Nic {
-    intcode: IntCode<isize>,
-    packet: Vec<isize>,
-    idle: usize,
-}
-
 pub struct Day23 {
-    nics: Vec<Nic>,
-    nat: [isize; 2],
+    network: Network<isize>,
 }
 
 impl Day23 {
     pub fn new() -> Self {
         Self {
-            nics: Vec::new(),
-            nat: [0; 2],
-        }
-    }
-
-    fn run<F>(&mut self, mut is_done: F)
-    where
-        F: FnMut(bool, isize) -> bool,
-    {
-        loop {
-            let mut idle_cnt = 0;
-            for i in 0..50 {
-                let nic = &mut self.nics[i];
-                if nic.intcode.is_stopped() {
-                    unreachable!();
-                }
-                match nic.intcode.run() {
-                    State::HasOutput(v) => {
-                        nic.packet.push(v);
-                        if nic.packet.len() == 3 {
-                            let to = nic.packet[0];
-                            let x = nic.packet[1];
-                            let y = nic.packet[2];
-                            nic.packet.clear();
-                            if to == 255 {
-                                self.nat[0] = x;
-                                self.nat[1] = y;
-                                if is_done(false, y) {
-                                    return;
-                                }
-                            } else {
-                                let to = &mut self.nics[to as usize];
-                                to.intcode.input.push_back(x);
-                                to.intcode.input.push_back(y);
-                                to.idle = 0;
-                            }
-                        }
-                    }
-                    State::WaitingForInput(..) => {
-                        nic.intcode.input.push_front(-1);
-                        if nic.idle != 0 {
-                            idle_cnt += 1;
-                        }
-                        nic.idle += 1;
-                    }
-                    State::Stopped => continue,
-                    x => unreachable!("Unexpected state: {x:?}"),
-                }
-            }
-            if idle_cnt == 50 {
-                if is_done(true, self.nat[1]) {
-                    return;
-                }
-                let nic = &mut self.nics[0];
-                nic.idle = 0;
-                nic.intcode.input.push_back(self.nat[0]);
-                nic.intcode.input.push_back(self.nat[1]);
-            }
+            network: Network::default(),
         }
     }
 }
@@ -83,15 +19,14 @@ impl Runner for Day23 {
         let mut master: IntCode<_> = IntCode::default();
         master.load(Lines::from_bufread(file, LinesOpt::RAW)?)?;
 
-        self.nics.extend((0..50).map(|i| {
-            let mut intcode = master.clone();
-            intcode.input.push_back(i);
-            Nic {
-                intcode,
-                packet: Vec::with_capacity(3),
-                idle: 0,
-            }
-        }));
+        let routing = Routing::Packets {
+            nat: 255,
+            no_packet: -1,
+        };
+        self.network = Network::new(&master, 50, routing);
+        for i in 0..50 {
+            self.network.send(i, &[i as isize]);
+        }
         Ok(())
     }
 
@@ -106,21 +41,28 @@ impl Runner for Day23 {
 
 impl Day23 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        self.run(|_, _| true);
-        Ok(self.nat[1].into())
+        loop {
+            match self.network.next_event()? {
+                Event::Nat([_, y]) => return Ok(y.into()),
+                Event::Idle | Event::Halted => return Err(Error::Unsolved),
+                _ => {}
+            }
+        }
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let mut last_y = isize::MAX;
-        self.run(|part2, y| {
-            if !part2 {
-                false
-            } else {
-                let is_done = last_y == y;
-                last_y = y;
-                is_done
+        let mut last_y = None;
+        loop {
+            match self.network.next_event()? {
+                Event::Wake([_, y]) => {
+                    if last_y == Some(y) {
+                        return Ok(y.into());
+                    }
+                    last_y = Some(y);
+                }
+                Event::Idle | Event::Halted => return Err(Error::Unsolved),
+                _ => {}
             }
-        });
-        Ok(self.nat[1].into())
+        }
     }
 }
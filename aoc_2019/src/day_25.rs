@@ -340,6 +340,7 @@ impl Day25 {
 
 impl Runner for Day25 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
+        self.intcode.set_predecode(true);
         self.intcode.load(Lines::from_bufread(file, LinesOpt::RAW)?)
     }
 
@@ -374,7 +375,7 @@ impl Day25 {
                     let (output, _) = output.split_once(' ').unwrap();
                     return Ok(output.parse::<isize>().unwrap().into());
                 }
-                x => unreachable!("Unexpected state: {x:?}"),
+                state => return Err(state.into()),
             }
         }
     }
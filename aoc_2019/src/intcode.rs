@@ -1,11 +1,18 @@
 use helper::{Error, Lines};
 use std::{
     collections::VecDeque,
+    fmt,
     ops::{Deref, DerefMut},
     str::FromStr,
 };
 
-pub(crate) trait Word:
+mod disasm;
+mod network;
+
+pub use disasm::{Arg, Instruction, Op};
+pub use network::{Event, Network, Routing, Stats};
+
+pub trait Word:
     Copy + Clone + From<u8> + FromStr + std::fmt::Display + std::fmt::Debug + PartialEq + PartialOrd
 {
     const ZERO: Self;
@@ -45,21 +52,105 @@ impl_word!(u32, u64, u128, usize);
 impl_word!(i32, i64, i128, isize);
 
 #[derive(Default, Copy, Clone, Debug)]
-pub(crate) enum State<T> {
+pub enum State<T> {
     #[default]
     Running,
     WaitingForInput(T, u8),
     HasOutput(T),
     Stopped,
+    Fault(Fault<T>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FaultKind {
+    InvalidOpcode,
+    InvalidMode,
+    NegativeAddress,
+    WriteToImmediate,
+    PcOutOfRange,
+}
+
+// Why the program could not continue, with the address and raw value of the instruction that
+// was executing.
+#[derive(Copy, Clone, Debug)]
+pub struct Fault<T> {
+    pub kind: FaultKind,
+    pub pc: usize,
+    pub instruction: T,
+}
+
+impl<T: Word> fmt::Display for Fault<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            FaultKind::InvalidOpcode => "invalid opcode",
+            FaultKind::InvalidMode => "invalid parameter mode",
+            FaultKind::NegativeAddress => "negative address",
+            FaultKind::WriteToImmediate => "write to immediate",
+            FaultKind::PcOutOfRange => "pc out of range",
+        };
+        write!(
+            f,
+            "{reason} at {} (instruction {})",
+            self.pc, self.instruction
+        )
+    }
+}
+
+impl<T: Word> From<Fault<T>> for Error {
+    fn from(fault: Fault<T>) -> Self {
+        Error::Runner(format!("IntCode fault: {fault}"))
+    }
+}
+
+// For a state the caller has no way to carry on from, such as a program stopping to wait for
+// input that a day never gives it
+impl<T: Word> From<State<T>> for Error {
+    fn from(state: State<T>) -> Self {
+        match state {
+            State::Fault(fault) => fault.into(),
+            State::Running => Error::Runner("IntCode still running".into()),
+            State::WaitingForInput(..) => Error::Runner("IntCode waiting for input".into()),
+            State::HasOutput(v) => Error::Runner(format!("IntCode gave unexpected output {v}")),
+            State::Stopped => Error::Runner("IntCode stopped unexpectedly".into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TraceEvent<T> {
+    Exec(Instruction<T>),
+    Write { addr: usize, old: T, new: T },
+    RelBase { old: T, new: T },
+}
+
+impl<T: Word> fmt::Display for TraceEvent<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exec(inst) => write!(f, "{inst}"),
+            Self::Write { addr, old, new } => write!(f, "       [{addr}] = {new} (was {old})"),
+            Self::RelBase { old, new } => write!(f, "       rb = {new} (was {old})"),
+        }
+    }
+}
+
+// An instruction with its parameter modes split out and its operands fetched.
+#[derive(Copy, Clone, Debug)]
+struct Decoded<T> {
+    opcode: u8,
+    modes: [u8; 3],
+    args: [T; 3],
+    width: usize,
 }
 
 #[derive(Default, Clone)]
-pub(crate) struct IntCode<T> {
+pub struct IntCode<T> {
     pc: usize,
     state: State<T>,
     mem: Vec<T>,
     relbase: T,
-    pub(crate) input: VecDeque<T>,
+    pub input: VecDeque<T>,
+    trace: Option<Vec<TraceEvent<T>>>,
+    decoded: Option<Vec<Option<Decoded<T>>>>,
 }
 
 impl<T> Deref for IntCode<T> {
@@ -72,6 +163,10 @@ impl<T> Deref for IntCode<T> {
 
 impl<T> DerefMut for IntCode<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        // Memory may be patched behind our back, so nothing decoded so far can be trusted.
+        if let Some(decoded) = self.decoded.as_mut() {
+            decoded.clear();
+        }
         &mut self.mem
     }
 }
@@ -81,7 +176,7 @@ where
     T: Word,
     helper::Error: From<<T as FromStr>::Err>,
 {
-    pub(crate) fn load(&mut self, lines: Lines) -> Result<(), Error> {
+    pub fn load(&mut self, lines: Lines) -> Result<(), Error> {
         self.mem.clear();
         for line in lines.iter() {
             for num in line.split(',') {
@@ -91,22 +186,28 @@ where
                 self.mem.push(num.parse()?)
             }
         }
+        if let Some(decoded) = self.decoded.as_mut() {
+            decoded.clear();
+        }
 
         Ok(())
     }
 
-    pub(crate) fn run(&mut self) -> State<T> {
+    pub fn run(&mut self) -> State<T> {
         loop {
             match self.state {
-                State::Stopped => return self.state,
+                State::Stopped | State::Fault(..) => return self.state,
                 State::WaitingForInput(rd, mode) => {
-                    if let Some(v) = self.input.pop_front() {
-                        self.write_mem(rd, v, mode);
-                    } else {
+                    let Some(v) = self.input.pop_front() else {
                         return self.state;
-                    }
+                    };
                     self.state = State::Running;
-                    self.tick();
+                    // The input instruction has already been fetched, so its address is two
+                    // back from the pc.
+                    match self.write_mem(rd, v, mode) {
+                        Ok(()) => self.tick(),
+                        Err(kind) => self.fault(kind, self.pc - 2),
+                    }
                 }
                 State::HasOutput(v) => {
                     self.state = State::Running;
@@ -122,13 +223,99 @@ where
     }
 
     pub fn is_stopped(&self) -> bool {
-        matches!(self.state, State::Stopped)
+        matches!(self.state, State::Stopped | State::Fault(..))
     }
 
-    fn get_from_pc(&mut self) -> T {
-        let val = self.mem[self.pc];
-        self.pc += 1;
-        val
+    // Decode each instruction once and reuse it until something writes over it, rather than
+    // decoding it again on every step.  Off unless asked for, and kept across loads and clones.
+    pub fn set_predecode(&mut self, enabled: bool) {
+        self.decoded = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    // Decode every address up front, so copies made afterwards share the work instead of each
+    // building their own cache.  Does nothing unless pre-decoding is on.
+    pub fn warm_predecode(&mut self) {
+        if self.decoded.is_some() {
+            self.decoded = Some((0..self.mem.len()).map(|pc| self.decode(pc).ok()).collect());
+        }
+    }
+
+    // Start or stop recording every executed instruction, memory write and relbase change.
+    pub fn set_trace(&mut self, enabled: bool) {
+        if enabled {
+            self.trace.get_or_insert_with(Vec::new);
+        } else {
+            self.trace = None;
+        }
+    }
+
+    pub fn take_trace(&mut self) -> Vec<TraceEvent<T>> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    pub fn write_trace(&mut self, path: &str) -> Result<(), Error> {
+        use std::io::Write;
+
+        let file =
+            std::fs::File::create(path).map_err(|e| Error::Runner(format!("{path}: {e}")))?;
+        let mut file = std::io::BufWriter::new(file);
+        for event in self.take_trace() {
+            writeln!(file, "{event}").map_err(|e| Error::Runner(format!("{path}: {e}")))?;
+        }
+        Ok(())
+    }
+
+    fn fault(&mut self, kind: FaultKind, pc: usize) {
+        self.state = State::Fault(Fault {
+            kind,
+            pc,
+            instruction: self.read_mem(pc),
+        });
+    }
+
+    fn decode(&self, pc: usize) -> Result<Decoded<T>, FaultKind> {
+        let word = *self.mem.get(pc).ok_or(FaultKind::PcOutOfRange)?;
+        if word < T::ZERO {
+            return Err(FaultKind::InvalidOpcode);
+        }
+        let word = word.usize();
+        let opcode = (word % 100) as u8;
+        let args = match opcode {
+            1 | 2 | 7 | 8 => 3,
+            5 | 6 => 2,
+            3 | 4 | 9 => 1,
+            99 => 0,
+            _ => return Err(FaultKind::InvalidOpcode),
+        };
+        let mut inst = Decoded {
+            opcode,
+            modes: [
+                ((word / 100) % 10) as u8,
+                ((word / 1000) % 10) as u8,
+                ((word / 10000) % 10) as u8,
+            ],
+            args: [T::ZERO; 3],
+            width: 1 + args,
+        };
+        for (i, arg) in inst.args.iter_mut().take(args).enumerate() {
+            *arg = *self.mem.get(pc + 1 + i).ok_or(FaultKind::PcOutOfRange)?;
+        }
+        Ok(inst)
+    }
+
+    fn fetch(&mut self) -> Result<Decoded<T>, FaultKind> {
+        let pc = self.pc;
+        if let Some(Some(inst)) = self.decoded.as_ref().and_then(|decoded| decoded.get(pc)) {
+            return Ok(*inst);
+        }
+        let inst = self.decode(pc)?;
+        if let Some(decoded) = self.decoded.as_mut() {
+            if decoded.len() <= pc {
+                decoded.resize(self.mem.len().max(pc + 1), None);
+            }
+            decoded[pc] = Some(inst);
+        }
+        Ok(inst)
     }
 
     fn read_mem(&self, addr: usize) -> T {
@@ -139,115 +326,149 @@ where
         }
     }
 
-    fn write_mem(&mut self, addr: T, v: T, mode: u8) {
+    fn write_mem(&mut self, addr: T, v: T, mode: u8) -> Result<(), FaultKind> {
         let addr = match mode {
             0 => addr,
-            1 => panic!("Cannot write to immediate"),
+            1 => return Err(FaultKind::WriteToImmediate),
             2 => self.relbase.wrapping_add(addr),
-            _ => unreachable!(),
+            _ => return Err(FaultKind::InvalidMode),
         };
         if addr < T::ZERO {
-            panic!("Address is less than 0.  {addr}");
+            return Err(FaultKind::NegativeAddress);
         }
         let addr = addr.usize();
+        if let Some(trace) = self.trace.as_mut() {
+            let old = self.mem.get(addr).copied().unwrap_or(T::ZERO);
+            trace.push(TraceEvent::Write { addr, old, new: v });
+        }
+        if let Some(decoded) = self.decoded.as_mut() {
+            // Any instruction overlapping the write starts at most 3 words before it
+            for inst in decoded
+                .iter_mut()
+                .take(addr + 1)
+                .skip(addr.saturating_sub(3))
+            {
+                *inst = None;
+            }
+        }
         if self.mem.len() <= addr {
             self.mem.resize(addr + 1, T::ZERO);
         }
         self.mem[addr] = v;
+        Ok(())
     }
 
-    fn get_arg(&mut self, mode: u8) -> T {
-        let arg = self.get_from_pc();
-        match mode {
-            0 => self.read_mem(arg.usize()),
-            1 => arg,
-            2 => {
-                let mem_loc = self.relbase.wrapping_add(arg);
-                if mem_loc < T::ZERO {
-                    panic!("Trying to access memory below 0");
-                }
-                self.read_mem(mem_loc.usize())
-            }
-            _ => unreachable!(),
+    fn get_arg(&self, arg: T, mode: u8) -> Result<T, FaultKind> {
+        let addr = match mode {
+            0 => arg,
+            1 => return Ok(arg),
+            2 => self.relbase.wrapping_add(arg),
+            _ => return Err(FaultKind::InvalidMode),
+        };
+        if addr < T::ZERO {
+            return Err(FaultKind::NegativeAddress);
         }
+        Ok(self.read_mem(addr.usize()))
+    }
+
+    fn jump(&mut self, target: T) -> Result<(), FaultKind> {
+        if target < T::ZERO {
+            return Err(FaultKind::PcOutOfRange);
+        }
+        self.pc = target.usize();
+        Ok(())
     }
 
     fn tick(&mut self) {
-        let opcode = self.get_from_pc().usize();
-        let mode_p3 = ((opcode / 10000) % 10) as u8;
-        let mode_p2 = ((opcode / 1000) % 10) as u8;
-        let mode_p1 = ((opcode / 100) % 10) as u8;
-        let opcode = opcode % 100;
-        match opcode {
+        let pc = self.pc;
+        if let Err(kind) = self.execute() {
+            self.fault(kind, pc);
+        }
+    }
+
+    fn execute(&mut self) -> Result<(), FaultKind> {
+        if let Some(trace) = self.trace.as_mut() {
+            if let Some(inst) = Instruction::decode(&self.mem, self.pc) {
+                trace.push(TraceEvent::Exec(inst));
+            }
+        }
+        let inst = self.fetch()?;
+        self.pc += inst.width;
+        let [mode_p1, mode_p2, mode_p3] = inst.modes;
+        let [p1, p2, p3] = inst.args;
+        match inst.opcode {
             1 => {
                 // Add rs1 rs2 rd
-                let rs1 = self.get_arg(mode_p1);
-                let rs2 = self.get_arg(mode_p2);
-                let rd = self.get_from_pc();
-                self.write_mem(rd, rs1.wrapping_add(rs2), mode_p3);
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let rs2 = self.get_arg(p2, mode_p2)?;
+                self.write_mem(p3, rs1.wrapping_add(rs2), mode_p3)?;
             }
             2 => {
                 // Mul rs1 rs2 rd
-                let rs1 = self.get_arg(mode_p1);
-                let rs2 = self.get_arg(mode_p2);
-                let rd = self.get_from_pc();
-                self.write_mem(rd, rs1.wrapping_mul(rs2), mode_p3);
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let rs2 = self.get_arg(p2, mode_p2)?;
+                self.write_mem(p3, rs1.wrapping_mul(rs2), mode_p3)?;
             }
             3 => {
                 // Input rd
-                let rd = self.get_from_pc();
                 if let Some(v) = self.input.pop_front() {
-                    self.write_mem(rd, v, mode_p1);
+                    self.write_mem(p1, v, mode_p1)?;
                 } else {
-                    self.state = State::WaitingForInput(rd, mode_p1);
+                    self.state = State::WaitingForInput(p1, mode_p1);
                 }
             }
             4 => {
                 // Output rs1
-                let rs1 = self.get_arg(mode_p1);
+                let rs1 = self.get_arg(p1, mode_p1)?;
                 self.state = State::HasOutput(rs1);
             }
             5 => {
                 // Jump-if-true rs1 target_pc
-                let rs1 = self.get_arg(mode_p1);
-                let target_pc = self.get_arg(mode_p2).usize();
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let target_pc = self.get_arg(p2, mode_p2)?;
 
                 if rs1 != T::ZERO {
-                    self.pc = target_pc;
+                    self.jump(target_pc)?;
                 }
             }
             6 => {
                 // Jump-if-false rs1 target_pc
-                let rs1 = self.get_arg(mode_p1);
-                let target_pc = self.get_arg(mode_p2).usize();
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let target_pc = self.get_arg(p2, mode_p2)?;
 
                 if rs1 == T::ZERO {
-                    self.pc = target_pc;
+                    self.jump(target_pc)?;
                 }
             }
             7 => {
                 // Less-than rs1 rs2 rd
-                let rs1 = self.get_arg(mode_p1);
-                let rs2 = self.get_arg(mode_p2);
-                let rd = self.get_from_pc();
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let rs2 = self.get_arg(p2, mode_p2)?;
 
-                self.write_mem(rd, if rs1 < rs2 { T::ONE } else { T::ZERO }, mode_p3);
+                self.write_mem(p3, if rs1 < rs2 { T::ONE } else { T::ZERO }, mode_p3)?;
             }
             8 => {
                 // Equals rs1 rs2 rd
-                let rs1 = self.get_arg(mode_p1);
-                let rs2 = self.get_arg(mode_p2);
-                let rd = self.get_from_pc();
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let rs2 = self.get_arg(p2, mode_p2)?;
 
-                self.write_mem(rd, if rs1 == rs2 { T::ONE } else { T::ZERO }, mode_p3);
+                self.write_mem(p3, if rs1 == rs2 { T::ONE } else { T::ZERO }, mode_p3)?;
             }
             9 => {
                 // Adjust relbase rs1
-                let rs1 = self.get_arg(mode_p1);
+                let rs1 = self.get_arg(p1, mode_p1)?;
+                let old = self.relbase;
                 self.relbase = self.relbase.wrapping_add(rs1);
+                if let Some(trace) = self.trace.as_mut() {
+                    trace.push(TraceEvent::RelBase {
+                        old,
+                        new: self.relbase,
+                    });
+                }
             }
             99 => self.state = State::Stopped,
-            _ => unreachable!(),
+            _ => return Err(FaultKind::InvalidOpcode),
         }
+        Ok(())
     }
 }
@@ -0,0 +1,210 @@
+use super::{IntCode, Word};
+use std::{collections::BTreeSet, fmt};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Mul,
+    In,
+    Out,
+    Jnz,
+    Jz,
+    Lt,
+    Eq,
+    Arb,
+    Hlt,
+}
+
+impl Op {
+    fn from_opcode(opcode: usize) -> Option<Self> {
+        Some(match opcode {
+            1 => Self::Add,
+            2 => Self::Mul,
+            3 => Self::In,
+            4 => Self::Out,
+            5 => Self::Jnz,
+            6 => Self::Jz,
+            7 => Self::Lt,
+            8 => Self::Eq,
+            9 => Self::Arb,
+            99 => Self::Hlt,
+            _ => return None,
+        })
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Mul => "mul",
+            Self::In => "in",
+            Self::Out => "out",
+            Self::Jnz => "jnz",
+            Self::Jz => "jz",
+            Self::Lt => "lt",
+            Self::Eq => "eq",
+            Self::Arb => "arb",
+            Self::Hlt => "hlt",
+        }
+    }
+
+    pub fn args(self) -> usize {
+        match self {
+            Self::Add | Self::Mul | Self::Lt | Self::Eq => 3,
+            Self::Jnz | Self::Jz => 2,
+            Self::In | Self::Out | Self::Arb => 1,
+            Self::Hlt => 0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Arg<T> {
+    pub mode: u8,
+    pub value: T,
+}
+
+impl<T: Word> fmt::Display for Arg<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            0 => write!(f, "[{}]", self.value),
+            1 => write!(f, "{}", self.value),
+            _ => write!(f, "[rb{:+}]", self.value),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Instruction<T> {
+    pub addr: usize,
+    pub op: Op,
+    pub args: Vec<Arg<T>>,
+}
+
+impl<T: Word> Instruction<T> {
+    // Decodes the instruction at `addr`, or returns None if it is not a valid opcode with valid
+    // parameter modes.
+    pub fn decode(mem: &[T], addr: usize) -> Option<Self> {
+        let opcode = *mem.get(addr)?;
+        if opcode < T::ZERO {
+            return None;
+        }
+        let opcode = opcode.usize();
+        let op = Op::from_opcode(opcode % 100)?;
+        let mut modes = opcode / 100;
+        let mut args = Vec::with_capacity(op.args());
+        for i in 0..op.args() {
+            let mode = (modes % 10) as u8;
+            modes /= 10;
+            if mode > 2 {
+                return None;
+            }
+            let value = *mem.get(addr + 1 + i)?;
+            args.push(Arg { mode, value });
+        }
+        if modes != 0 {
+            return None;
+        }
+        Some(Self { addr, op, args })
+    }
+
+    pub fn size(&self) -> usize {
+        1 + self.args.len()
+    }
+
+    // Next instructions that can execute after this one, when they can be known statically.
+    fn successors(&self) -> (Option<usize>, Option<usize>) {
+        let next = self.addr + self.size();
+        match self.op {
+            Op::Hlt => (None, None),
+            Op::Jnz | Op::Jz => {
+                let target = self.args[1];
+                let target = if target.mode == 1 && target.value >= T::ZERO {
+                    Some(target.value.usize())
+                } else {
+                    None
+                };
+                // A jump on an immediate condition always or never goes one way
+                match (self.args[0].mode, self.args[0].value == T::ZERO, self.op) {
+                    (1, false, Op::Jnz) | (1, true, Op::Jz) => (target, None),
+                    (1, _, _) => (Some(next), None),
+                    _ => (Some(next), target),
+                }
+            }
+            _ => (Some(next), None),
+        }
+    }
+}
+
+impl<T: Word> fmt::Display for Instruction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:5}: {:3}", self.addr, self.op.mnemonic())?;
+        for (i, arg) in self.args.iter().enumerate() {
+            write!(f, "{}{arg}", if i == 0 { " " } else { ", " })?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> IntCode<T>
+where
+    T: Word,
+{
+    // Follows every statically reachable path from address 0 and returns the start of each
+    // instruction found.  Anything else is treated as data.
+    pub fn code_addresses(&self) -> BTreeSet<usize> {
+        let mut code = BTreeSet::new();
+        let mut pending = vec![0];
+        while let Some(addr) = pending.pop() {
+            if code.contains(&addr) {
+                continue;
+            }
+            let Some(inst) = Instruction::decode(&self.mem, addr) else {
+                continue;
+            };
+            code.insert(addr);
+            let (a, b) = inst.successors();
+            pending.extend(a);
+            pending.extend(b);
+        }
+        code
+    }
+
+    // Annotated listing of the loaded program with jump targets labelled and data shown as
+    // rows of raw values.
+    pub fn disassemble(&self) -> String {
+        use std::fmt::Write;
+
+        let code = self.code_addresses();
+        let targets: BTreeSet<usize> = code
+            .iter()
+            .filter_map(|addr| Instruction::decode(&self.mem, *addr))
+            .filter(|inst| matches!(inst.op, Op::Jnz | Op::Jz))
+            .filter(|inst| inst.args[1].mode == 1 && inst.args[1].value >= T::ZERO)
+            .map(|inst| inst.args[1].value.usize())
+            .collect();
+
+        let mut out = String::new();
+        let mut addr = 0;
+        while addr < self.mem.len() {
+            if code.contains(&addr) {
+                let inst = Instruction::decode(&self.mem, addr).expect("Decoded before");
+                if targets.contains(&addr) {
+                    let _ = writeln!(out, "L{addr}:");
+                }
+                let _ = writeln!(out, "{inst}");
+                addr += inst.size();
+            } else {
+                let start = addr;
+                while addr < self.mem.len() && !code.contains(&addr) && addr - start < 8 {
+                    addr += 1;
+                }
+                let _ = write!(out, "{start:5}: data");
+                for v in self.mem[start..addr].iter() {
+                    let _ = write!(out, " {v}");
+                }
+                let _ = writeln!(out);
+            }
+        }
+        out
+    }
+}
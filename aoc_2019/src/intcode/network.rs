@@ -0,0 +1,188 @@
+use super::{IntCode, State, Word};
+use helper::Error;
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+// How values output by one machine reach the others.
+#[derive(Default, Copy, Clone, Debug)]
+pub enum Routing<T> {
+    // Each machine feeds the next and the last machine outputs to the network.
+    #[default]
+    Pipeline,
+    // As a pipeline, but the last machine also feeds back into the first.
+    Ring,
+    // Outputs are grouped into `address, x, y` packets.  Packets for `nat` are held and sent to
+    // machine 0 once the whole network is idle.  A machine with nothing to read is given
+    // `no_packet`.
+    Packets {
+        nat: usize,
+        no_packet: T,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event<T> {
+    // The last machine of a pipeline or ring output a value.
+    Output(T),
+    // A packet was sent to the NAT.
+    Nat([T; 2]),
+    // The network was idle and the NAT sent its last packet to machine 0.
+    Wake([T; 2]),
+    // Every machine is waiting for input that will never come.
+    Idle,
+    // Every machine has stopped.
+    Halted,
+}
+
+#[derive(Default, Copy, Clone, Debug)]
+pub struct Stats {
+    pub sent: usize,
+    pub received: usize,
+    pub idle_polls: usize,
+}
+
+#[derive(Default, Clone)]
+pub struct Network<T> {
+    machines: Vec<IntCode<T>>,
+    routing: Routing<T>,
+    packets: Vec<Vec<T>>,
+    idle: Vec<usize>,
+    stats: Vec<Stats>,
+    nat: Option<[T; 2]>,
+    events: VecDeque<Event<T>>,
+}
+
+impl<T> Deref for Network<T> {
+    type Target = [IntCode<T>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.machines
+    }
+}
+
+impl<T> DerefMut for Network<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.machines
+    }
+}
+
+impl<T> Network<T>
+where
+    T: Word,
+    Error: From<<T as FromStr>::Err>,
+{
+    pub fn new(program: &IntCode<T>, n: usize, routing: Routing<T>) -> Self {
+        Self {
+            machines: vec![program.clone(); n],
+            routing,
+            packets: vec![Vec::with_capacity(3); n],
+            idle: vec![0; n],
+            stats: vec![Stats::default(); n],
+            nat: None,
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn stats(&self) -> &[Stats] {
+        &self.stats
+    }
+
+    pub fn send(&mut self, to: usize, values: &[T]) {
+        self.machines[to].input.extend(values.iter().copied());
+        self.stats[to].received += values.len();
+        self.idle[to] = 0;
+    }
+
+    // Runs the machines round robin until something happens that the caller may care about.
+    pub fn next_event(&mut self) -> Result<Event<T>, Error> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+            self.sweep()?;
+        }
+    }
+
+    fn sweep(&mut self) -> Result<(), Error> {
+        let n = self.machines.len();
+        for i in 0..n {
+            match self.machines[i].run() {
+                State::HasOutput(v) => {
+                    self.stats[i].sent += 1;
+                    self.route(i, v)?;
+                }
+                State::WaitingForInput(..) => {
+                    self.stats[i].idle_polls += 1;
+                    self.idle[i] += 1;
+                    if let Routing::Packets { no_packet, .. } = self.routing {
+                        self.machines[i].input.push_back(no_packet);
+                    }
+                }
+                State::Stopped => {}
+                State::Fault(fault) => return Err(fault.into()),
+                State::Running => unreachable!(),
+            }
+        }
+
+        // A machine being fed empty packets has to ask twice before it counts as idle, the
+        // first poll may just be it catching up on earlier input.
+        let polls = if let Routing::Packets { .. } = self.routing {
+            2
+        } else {
+            1
+        };
+        let mut running = (0..n)
+            .filter(|i| !self.machines[*i].is_stopped())
+            .peekable();
+        if running.peek().is_none() {
+            self.events.push_back(Event::Halted);
+        } else if running.all(|i| self.idle[i] >= polls) {
+            match self.nat {
+                Some(packet) if n > 0 => {
+                    self.send(0, &packet);
+                    self.events.push_back(Event::Wake(packet));
+                }
+                _ => self.events.push_back(Event::Idle),
+            }
+        }
+        Ok(())
+    }
+
+    fn route(&mut self, from: usize, v: T) -> Result<(), Error> {
+        let n = self.machines.len();
+        match self.routing {
+            Routing::Pipeline | Routing::Ring => {
+                if from + 1 < n {
+                    self.send(from + 1, &[v]);
+                } else {
+                    self.events.push_back(Event::Output(v));
+                    if let Routing::Ring = self.routing {
+                        self.send(0, &[v]);
+                    }
+                }
+            }
+            Routing::Packets { nat, .. } => {
+                self.packets[from].push(v);
+                if self.packets[from].len() == 3 {
+                    let (to, packet) = (self.packets[from][0], [self.packets[from][1], v]);
+                    self.packets[from].clear();
+                    if to < T::ZERO {
+                        return Err(Error::Runner(format!("Packet sent to address {to}")));
+                    }
+                    match to.usize() {
+                        to if to == nat => {
+                            self.nat = Some(packet);
+                            self.events.push_back(Event::Nat(packet));
+                        }
+                        to if to < n => self.send(to, &packet),
+                        to => return Err(Error::Runner(format!("Packet sent to address {to}"))),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use common::dot::{dump, NewDump};
 use helper::NewRunner;
 use std::collections::BTreeMap;
 
@@ -26,7 +27,7 @@ mod day_22;
 mod day_23;
 mod day_24;
 mod day_25;
-pub(crate) mod intcode;
+pub mod intcode;
 
 pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2019, 1), (2, || Box::new(day_01::Day01::new())));
@@ -56,3 +57,6 @@ pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2019, 25), (1, || Box::new(day_25::Day25::new())));
 }
 
+pub fn register_dumps(dumps: &mut BTreeMap<(usize, usize), NewDump>) {
+    dumps.insert((2019, 6), |input| dump(day_06::Day06::new(), input));
+}
@@ -0,0 +1 @@
+common::sample_tests!(aoc_2019::register, 2019);
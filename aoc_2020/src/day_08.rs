@@ -1,42 +1,73 @@
+use asm::{Args, Effect, InstructionSet, Machine};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
 #[derive(Copy, Clone)]
 enum Op {
-    Nop,
-    Acc,
-    Jmp,
+    Nop(isize),
+    Acc(isize),
+    Jmp(isize),
 }
 
+impl Op {
+    fn swapped(self) -> Option<Self> {
+        match self {
+            Self::Nop(num) => Some(Self::Jmp(num)),
+            Self::Jmp(num) => Some(Self::Nop(num)),
+            Self::Acc(_) => None,
+        }
+    }
+}
+
+impl InstructionSet for Op {
+    const REGISTERS: &'static [&'static str] = &["acc"];
+
+    fn parse(op: &str, args: &Args) -> Result<Self, Error> {
+        match op {
+            "nop" => Ok(Self::Nop(args.imm(0)?)),
+            "acc" => Ok(Self::Acc(args.imm(0)?)),
+            "jmp" => Ok(Self::Jmp(args.imm(0)?)),
+            _ => Err(args.invalid()),
+        }
+    }
+
+    fn execute(self, machine: &mut Machine<Self>) -> Effect {
+        match self {
+            Self::Nop(_) => Effect::Next,
+            Self::Acc(num) => {
+                machine.registers[0] += num;
+                Effect::Next
+            }
+            Self::Jmp(num) => Effect::Jump(num),
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct Day08 {
-    inst: Vec<(Op, isize)>,
+    machine: Machine<Op>,
 }
 
 impl Day08 {
     pub fn new() -> Self {
-        Self { inst: Vec::new() }
+        Self::default()
     }
 
-    fn run(&self) -> Result<isize, isize> {
-        let mut pc = 0;
-        let mut acc = 0;
+    fn run(&mut self) -> Result<isize, isize> {
+        let machine = &mut self.machine;
+        machine.reset();
         let mut seen = HashSet::default();
         loop {
-            if pc == self.inst.len() {
-                return Ok(acc);
-            } else if pc > self.inst.len() {
-                unreachable!();
-            } else if !seen.insert(pc) {
-                return Err(acc);
-            }
-            match self.inst[pc] {
-                (Op::Nop, _) => pc += 1,
-                (Op::Acc, num) => {
-                    acc += num;
-                    pc += 1;
+            let acc = machine.registers[0];
+            if machine.is_halted() {
+                if machine.pc as usize == machine.program.len() {
+                    return Ok(acc);
                 }
-                (Op::Jmp, num) => pc = pc.wrapping_add(num as usize),
+                return Err(acc);
+            } else if !seen.insert(machine.pc) {
+                return Err(acc);
             }
+            machine.step();
         }
     }
 }
@@ -44,18 +75,7 @@ impl Day08 {
 impl Runner for Day08 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for line in lines.iter() {
-            let (op, num) = line.split_once(' ').unwrap();
-            let num: isize = num.parse()?;
-            let op = match op {
-                "nop" => Op::Nop,
-                "acc" => Op::Acc,
-                "jmp" => Op::Jmp,
-                _ => unreachable!(),
-            };
-
-            self.inst.push((op, num));
-        }
+        self.machine = Machine::parse(lines.iter())?;
         Ok(())
     }
 
@@ -77,23 +97,17 @@ impl Day08 {
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        for i in 0..self.inst.len() {
-            let prev = match self.inst[i].0 {
-                Op::Nop => {
-                    self.inst[i].0 = Op::Jmp;
-                    Op::Nop
-                }
-                Op::Jmp => {
-                    self.inst[i].0 = Op::Nop;
-                    Op::Jmp
-                }
-                Op::Acc => continue,
+        for i in 0..self.machine.program.len() {
+            let prev = self.machine.program[i];
+            let Some(swapped) = prev.swapped() else {
+                continue;
             };
 
+            self.machine.program[i] = swapped;
             if let Ok(num) = self.run() {
                 return Ok(num.into());
             }
-            self.inst[i].0 = prev;
+            self.machine.program[i] = prev;
         }
         Err(Error::Unsolved)
     }
@@ -1,3 +1,4 @@
+use common::grid::{Cell, Grid, DIRS8};
 #[allow(unused_imports)]
 use helper::{
     print, println, BitGrid, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner,
@@ -10,100 +11,75 @@ enum Spot {
     Occuipied,
 }
 
+impl Cell for Spot {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Self::Floor),
+            'L' => Some(Self::Vacant),
+            '#' => Some(Self::Occuipied),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Self::Floor => '.',
+            Self::Vacant => 'L',
+            Self::Occuipied => '#',
+        }
+    }
+}
+
 pub struct Day11 {
-    tiles: Vec<Vec<[Spot; 2]>>,
+    tiles: Grid<Spot>,
 }
 
 impl Day11 {
     pub fn new() -> Self {
-        Self { tiles: Vec::new() }
+        Self {
+            tiles: Grid::default(),
+        }
     }
 
     fn simulate<const MAX_DIST: usize, const SURROUND: usize>(&mut self) -> usize {
-        let height = self.tiles.len();
-        let width = self.tiles[0].len();
-        for round in 0.. {
-            let cur = if round % 2 == 0 { 0 } else { 1 };
-            let next = 1 - cur;
+        let mut cur = self.tiles.clone();
+        let mut next = self.tiles.clone();
+        loop {
             let mut changed = false;
-
-            for y in 0..height {
-                for x in 0..width {
-                    if self.tiles[y][x][cur] == Spot::Floor {
-                        continue;
-                    }
-                    let mut neighbors = 0;
-                    for (dx, dy) in [
-                        (-1, -1),
-                        (0, -1),
-                        (1, -1),
-                        (-1, 0),
-                        (1, 0),
-                        (-1, 1),
-                        (0, 1),
-                        (1, 1),
-                    ]
-                    .iter()
-                    {
-                        for d in 1..=MAX_DIST {
-                            let nx = (x as isize + (d as isize * dx)) as usize;
-                            let ny = (y as isize + (d as isize * dy)) as usize;
-                            if nx >= width || ny >= height {
-                                break;
-                            }
-                            match self.tiles[ny][nx][cur] {
-                                Spot::Vacant => break,
-                                Spot::Occuipied => {
-                                    neighbors += 1;
-                                    break;
-                                }
-                                Spot::Floor => {}
-                            }
-                        }
-                    }
-                    self.tiles[y][x][next] = Spot::Vacant;
-                    if self.tiles[y][x][cur] == Spot::Vacant {
-                        if neighbors == 0 {
-                            self.tiles[y][x][next] = Spot::Occuipied;
-                            changed = true;
-                        }
-                    } else if neighbors < SURROUND {
-                        self.tiles[y][x][next] = Spot::Occuipied;
-                    } else {
-                        changed = true;
-                    }
+            for (pos, spot) in cur.iter() {
+                if *spot == Spot::Floor {
+                    continue;
                 }
+                let neighbors = DIRS8
+                    .iter()
+                    .filter(|dir| {
+                        cur.ray(pos, **dir)
+                            .take(MAX_DIST)
+                            .map(|seat| cur[seat])
+                            .find(|seat| *seat != Spot::Floor)
+                            == Some(Spot::Occuipied)
+                    })
+                    .count();
+                next[pos] = match spot {
+                    Spot::Vacant if neighbors == 0 => Spot::Occuipied,
+                    Spot::Occuipied if neighbors >= SURROUND => Spot::Vacant,
+                    spot => *spot,
+                };
+                changed |= next[pos] != *spot;
             }
 
             if !changed {
-                return self.tiles.iter().fold(0, |count, row| {
-                    count
-                        + row
-                            .iter()
-                            .filter(|s| matches!(s[cur], Spot::Occuipied))
-                            .count()
-                });
+                return cur.iter().filter(|(_, s)| **s == Spot::Occuipied).count();
             }
+            std::mem::swap(&mut cur, &mut next);
         }
-        0
     }
 }
 
 impl Runner for Day11 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for line in lines.iter() {
-            self.tiles.push(
-                line.chars()
-                    .map(|c| match c {
-                        '.' => [Spot::Floor, Spot::Floor],
-                        'L' => [Spot::Vacant, Spot::Vacant],
-                        '#' => [Spot::Occuipied, Spot::Vacant],
-                        _ => unreachable!(),
-                    })
-                    .collect(),
-            );
-        }
+        self.tiles = Grid::parse(lines.iter())?;
         Ok(())
     }
 
@@ -1,3 +1,4 @@
+use common::rotation::Orientation;
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -44,33 +45,15 @@ impl Tile {
         self.edge_connections[edge] = Some(connect_to);
     }
 
-    fn rotate(&mut self, count: usize, flip_x: bool, flip_y: bool) {
-        assert!(!self.is_rotated || (count == 0 && !flip_x && !flip_y));
+    fn orient(&mut self, orientation: &Orientation<2>) {
+        assert!(!self.is_rotated || *orientation == Orientation::identity());
 
         if self.is_rotated {
             return;
         }
 
         self.is_rotated = true;
-        for _ in 0..count {
-            let mut new_grid = [[' '; 10]; 10];
-            for (y, row) in new_grid.iter_mut().enumerate() {
-                for (x, cell) in row.iter_mut().enumerate() {
-                    *cell = self.grid[9 - x][y];
-                }
-            }
-
-            self.grid = new_grid;
-        }
-
-        if flip_x {
-            self.grid.iter_mut().for_each(|row| row.reverse());
-        }
-
-        if flip_y {
-            self.grid.reverse();
-        }
-
+        self.grid = orientation.apply_square(&self.grid);
         self.calculate_edges();
     }
 }
@@ -94,12 +77,10 @@ impl Day20 {
         let mut all_rotations = HashMap::default();
         for num in tile_nums.iter() {
             let mut tile_rotations = Vec::new();
-            for r in 0..4 {
-                for flip in 0..3 {
-                    let mut tile = self.tiles.get(num).unwrap().clone();
-                    tile.rotate(r, flip == 1, flip == 2);
-                    tile_rotations.push(tile);
-                }
+            for orientation in Orientation::all() {
+                let mut tile = self.tiles.get(num).unwrap().clone();
+                tile.orient(&orientation);
+                tile_rotations.push(tile);
             }
             all_rotations.insert(num, tile_rotations);
         }
@@ -277,56 +258,32 @@ impl Day20 {
             width: usize,
             height: usize,
         }
-        fn rotate(sea_monster: &[&str], count: usize, flip_x: bool, flip_y: bool) -> SeaMonster {
-            let mut sea_monster: Vec<Vec<char>> = sea_monster
-                .iter()
-                .map(|row| row.chars().collect())
-                .collect();
-            for _ in 0..count {
-                let mut new_sea_monster = Vec::new();
-                for x in 0..sea_monster[0].len() {
-                    let mut row = Vec::new();
-                    for y in (0..sea_monster.len()).rev() {
-                        row.push(sea_monster[y][x]);
-                    }
-                    new_sea_monster.push(row);
+        let mut points = Vec::new();
+        for (y, row) in sea_monster_array.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    points.push([x as isize, y as isize]);
                 }
-
-                sea_monster = new_sea_monster;
-            }
-
-            if flip_x {
-                sea_monster.iter_mut().for_each(|row| row.reverse());
-            }
-
-            if flip_y {
-                sea_monster.reverse();
             }
+        }
 
-            let mut deltas = Vec::new();
-            sea_monster.iter().enumerate().for_each(|(y, row)| {
-                row.iter().enumerate().for_each(|(x, c)| {
-                    if *c == '#' {
-                        deltas.push((x, y));
-                    }
-                })
-            });
+        let mut sea_monsters = Vec::new();
+        for orientation in Orientation::all() {
+            let points = orientation.apply_all(&points);
+            let min_x = points.iter().map(|p| p[0]).min().unwrap();
+            let min_y = points.iter().map(|p| p[1]).min().unwrap();
+            let deltas: Vec<(usize, usize)> = points
+                .iter()
+                .map(|p| ((p[0] - min_x) as usize, (p[1] - min_y) as usize))
+                .collect();
 
             let width = deltas.iter().map(|c| c.0).max().unwrap() + 1;
             let height = deltas.iter().map(|c| c.1).max().unwrap() + 1;
-            SeaMonster {
+            sea_monsters.push(SeaMonster {
                 deltas,
                 width,
                 height,
-            }
-        }
-
-        let mut sea_monsters = Vec::new();
-        for r in 0..4 {
-            for flip in 0..3 {
-                let sea_monster = rotate(&sea_monster_array, r, flip == 1, flip == 2);
-                sea_monsters.push(sea_monster);
-            }
+            });
         }
 
         let mut sea_monster_waves = HashSet::default();
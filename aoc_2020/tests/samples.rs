@@ -0,0 +1 @@
+common::sample_tests!(aoc_2020::register, 2020);
@@ -0,0 +1,380 @@
+use helper::Error;
+use std::fmt;
+
+// The operators a packet can apply to the packets inside it, by their type ID
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Sum,
+    Product,
+    Min,
+    Max,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Op {
+    const LITERAL: u64 = 4;
+
+    fn from_type_id(id: u64) -> Option<Self> {
+        match id {
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Min),
+            3 => Some(Self::Max),
+            5 => Some(Self::GreaterThan),
+            6 => Some(Self::LessThan),
+            7 => Some(Self::EqualTo),
+            _ => None,
+        }
+    }
+
+    fn type_id(self) -> u64 {
+        match self {
+            Self::Sum => 0,
+            Self::Product => 1,
+            Self::Min => 2,
+            Self::Max => 3,
+            Self::GreaterThan => 5,
+            Self::LessThan => 6,
+            Self::EqualTo => 7,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::GreaterThan => "gt",
+            Self::LessThan => "lt",
+            Self::EqualTo => "eq",
+        }
+    }
+}
+
+// How an operator packet says where the packets inside it end: by their total length in bits,
+// written in 15 bits, or by how many there are, written in 11 bits
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LengthType {
+    Bits,
+    Packets,
+}
+
+impl LengthType {
+    fn width(self) -> usize {
+        match self {
+            Self::Bits => 15,
+            Self::Packets => 11,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Body {
+    Literal(u64),
+    Operator(Op, Vec<Packet>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub body: Body,
+}
+
+// Why a transmission could not be read, written or evaluated.  Positions are in bits from the
+// start of the transmission.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BitsError {
+    InvalidHex { at: usize, c: char },
+    Truncated { at: usize },
+    LiteralTooLarge { at: usize },
+    // Packets inside an operator ran past the number of bits it gave them
+    LengthOverrun { at: usize, len: usize },
+    InvalidVersion(u8),
+    TooManyPackets(usize),
+    TooManyBits(usize),
+    WrongOperands { op: Op, count: usize },
+    Overflow(Op),
+}
+
+impl fmt::Display for BitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex { at, c } => write!(f, "invalid hex digit {c:?} at bit {at}"),
+            Self::Truncated { at } => write!(f, "transmission ends at bit {at}"),
+            Self::LiteralTooLarge { at } => write!(f, "literal at bit {at} is over 64 bits"),
+            Self::LengthOverrun { at, len } => {
+                write!(
+                    f,
+                    "packets from bit {at} run past their length of {len} bits"
+                )
+            }
+            Self::InvalidVersion(v) => write!(f, "version {v} does not fit in 3 bits"),
+            Self::TooManyPackets(n) => write!(f, "{n} packets do not fit in one operator"),
+            Self::TooManyBits(n) => write!(f, "{n} bits of packets do not fit in one operator"),
+            Self::WrongOperands { op, count } => {
+                write!(f, "{} cannot take {count} operands", op.name())
+            }
+            Self::Overflow(op) => write!(f, "{} overflows 64 bits", op.name()),
+        }
+    }
+}
+
+impl From<BitsError> for Error {
+    fn from(e: BitsError) -> Self {
+        Error::InvalidInput(format!("BITS: {e}"))
+    }
+}
+
+struct BitReader {
+    bits: Vec<bool>,
+    pos: usize,
+}
+
+impl BitReader {
+    fn from_hex(hex: &str) -> Result<Self, BitsError> {
+        let mut bits = Vec::with_capacity(hex.len() * 4);
+        for (i, c) in hex.chars().enumerate() {
+            let Some(v) = c.to_digit(16) else {
+                return Err(BitsError::InvalidHex { at: i * 4, c });
+            };
+            bits.extend((0..4).rev().map(|shift| v >> shift & 1 == 1));
+        }
+        Ok(Self { bits, pos: 0 })
+    }
+
+    fn read(&mut self, width: usize) -> Result<u64, BitsError> {
+        let Some(bits) = self.bits.get(self.pos..self.pos + width) else {
+            return Err(BitsError::Truncated {
+                at: self.bits.len(),
+            });
+        };
+        self.pos += width;
+        Ok(bits.iter().fold(0, |v, &b| v << 1 | b as u64))
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bits: Vec<bool>,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, width: usize) {
+        self.bits
+            .extend((0..width).rev().map(|shift| value >> shift & 1 == 1));
+    }
+
+    // Padded with zeros to whole bytes, as transmissions are
+    fn to_hex(&self) -> String {
+        let mut bits = self.bits.clone();
+        bits.resize(bits.len().div_ceil(8) * 8, false);
+        bits.chunks(4)
+            .map(|digit| {
+                let v = digit.iter().fold(0, |v, &b| v << 1 | b as u32);
+                char::from_digit(v, 16)
+                    .expect("4 bits")
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Self {
+        Self {
+            version,
+            body: Body::Literal(value),
+        }
+    }
+
+    pub fn operator(version: u8, op: Op, packets: Vec<Packet>) -> Self {
+        Self {
+            version,
+            body: Body::Operator(op, packets),
+        }
+    }
+
+    // Reads the outermost packet of a transmission, ignoring the padding after it
+    pub fn decode(hex: &str) -> Result<Self, BitsError> {
+        Self::read(&mut BitReader::from_hex(hex)?)
+    }
+
+    fn read(reader: &mut BitReader) -> Result<Self, BitsError> {
+        let at = reader.pos;
+        let version = reader.read(3)? as u8;
+        let type_id = reader.read(3)?;
+        if type_id == Op::LITERAL {
+            let mut value = 0u64;
+            loop {
+                let group = reader.read(5)?;
+                if value >> 60 != 0 {
+                    return Err(BitsError::LiteralTooLarge { at });
+                }
+                value = value << 4 | group & 0b1111;
+                if group & 0b10000 == 0 {
+                    return Ok(Self::literal(version, value));
+                }
+            }
+        }
+        let op = Op::from_type_id(type_id).expect("3 bit IDs other than literals are operators");
+
+        let mut packets = Vec::new();
+        if reader.read(1)? == 0 {
+            let len = reader.read(LengthType::Bits.width())? as usize;
+            let start = reader.pos;
+            while reader.pos < start + len {
+                packets.push(Self::read(reader)?);
+            }
+            if reader.pos != start + len {
+                return Err(BitsError::LengthOverrun { at: start, len });
+            }
+        } else {
+            let count = reader.read(LengthType::Packets.width())?;
+            for _ in 0..count {
+                packets.push(Self::read(reader)?);
+            }
+        }
+        Ok(Self::operator(version, op, packets))
+    }
+
+    // Writes the packet as a transmission, giving each operator the count of the packets inside
+    // it unless there are too many, and their length in bits then
+    pub fn encode(&self) -> Result<String, BitsError> {
+        self.encode_with(None)
+    }
+
+    // As `encode`, but giving every operator the same length type, which must fit
+    pub fn encode_with(&self, length_type: Option<LengthType>) -> Result<String, BitsError> {
+        let mut writer = BitWriter::default();
+        self.write(&mut writer, length_type)?;
+        Ok(writer.to_hex())
+    }
+
+    fn write(
+        &self,
+        writer: &mut BitWriter,
+        length_type: Option<LengthType>,
+    ) -> Result<(), BitsError> {
+        if self.version > 7 {
+            return Err(BitsError::InvalidVersion(self.version));
+        }
+        writer.write(self.version as u64, 3);
+        match &self.body {
+            Body::Literal(value) => {
+                writer.write(Op::LITERAL, 3);
+                let groups = (64 - value.leading_zeros() as usize).div_ceil(4).max(1);
+                for g in (0..groups).rev() {
+                    let more = if g == 0 { 0 } else { 0b10000 };
+                    writer.write(more | value >> (g * 4) & 0b1111, 5);
+                }
+            }
+            Body::Operator(op, packets) => {
+                writer.write(op.type_id(), 3);
+                let fits_count = packets.len() < 1 << LengthType::Packets.width();
+                match length_type {
+                    Some(LengthType::Packets) | None if fits_count => {
+                        writer.write(1, 1);
+                        writer.write(packets.len() as u64, LengthType::Packets.width());
+                        for packet in packets {
+                            packet.write(writer, length_type)?;
+                        }
+                    }
+                    Some(LengthType::Packets) => {
+                        return Err(BitsError::TooManyPackets(packets.len()));
+                    }
+                    Some(LengthType::Bits) | None => {
+                        let mut inner = BitWriter::default();
+                        for packet in packets {
+                            packet.write(&mut inner, length_type)?;
+                        }
+                        let len = inner.bits.len();
+                        if len >= 1 << LengthType::Bits.width() {
+                            return Err(BitsError::TooManyBits(len));
+                        }
+                        writer.write(0, 1);
+                        writer.write(len as u64, LengthType::Bits.width());
+                        writer.bits.extend(inner.bits);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        self.version as u64
+            + match &self.body {
+                Body::Literal(_) => 0,
+                Body::Operator(_, packets) => packets.iter().map(Self::version_sum).sum(),
+            }
+    }
+
+    pub fn eval(&self) -> Result<u64, BitsError> {
+        let (op, packets) = match &self.body {
+            Body::Literal(value) => return Ok(*value),
+            Body::Operator(op, packets) => (*op, packets),
+        };
+        let values = packets
+            .iter()
+            .map(Self::eval)
+            .collect::<Result<Vec<_>, _>>()?;
+        let wrong_operands = BitsError::WrongOperands {
+            op,
+            count: values.len(),
+        };
+        match op {
+            Op::Sum => values
+                .iter()
+                .try_fold(0u64, |acc, &v| acc.checked_add(v))
+                .ok_or(BitsError::Overflow(op)),
+            Op::Product => values
+                .iter()
+                .try_fold(1u64, |acc, &v| acc.checked_mul(v))
+                .ok_or(BitsError::Overflow(op)),
+            Op::Min => values.iter().copied().min().ok_or(wrong_operands),
+            Op::Max => values.iter().copied().max().ok_or(wrong_operands),
+            Op::GreaterThan | Op::LessThan | Op::EqualTo => {
+                let &[a, b] = values.as_slice() else {
+                    return Err(wrong_operands);
+                };
+                Ok(match op {
+                    Op::GreaterThan => a > b,
+                    Op::LessThan => a < b,
+                    _ => a == b,
+                } as u64)
+            }
+        }
+    }
+}
+
+// The packet tree as an S-expression with each packet's version after a 'v', such as
+// `(sum v3 (lit v1 5) (lit v6 7))`.  The alternate form puts each packet inside an operator on
+// its own line, indented under it.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_sexpr(f, 0)
+    }
+}
+
+impl Packet {
+    fn write_sexpr(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match &self.body {
+            Body::Literal(value) => write!(f, "(lit v{} {value})", self.version),
+            Body::Operator(op, packets) => {
+                write!(f, "({} v{}", op.name(), self.version)?;
+                for packet in packets {
+                    if f.alternate() {
+                        write!(f, "\n{:1$}", "", (depth + 1) * 2)?;
+                    } else {
+                        write!(f, " ")?;
+                    }
+                    packet.write_sexpr(f, depth + 1)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
@@ -14,8 +14,9 @@ impl Day01 {
 impl Runner for Day01 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        self.depths
-            .extend(lines.iter().map(|l| l.parse::<usize>().unwrap()));
+        for line in lines.iter() {
+            self.depths.push(line.parse()?);
+        }
         Ok(())
     }
 
@@ -1,7 +1,9 @@
+use common::dot::{Dot, GraphDump};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
 pub struct Day12 {
+    names: Vec<String>,
     caves: Vec<Cave>,
     paths: Vec<Vec<usize>>,
 }
@@ -17,6 +19,7 @@ enum Cave {
 impl Day12 {
     pub fn new() -> Self {
         Self {
+            names: Vec::new(),
             caves: Vec::new(),
             paths: Vec::new(),
         }
@@ -65,6 +68,26 @@ impl Day12 {
     }
 }
 
+impl GraphDump for Day12 {
+    fn dot(&self) -> Dot {
+        let mut dot = Dot::graph("caves");
+        for (name, cave) in self.names.iter().zip(self.caves.iter()) {
+            let shape = match cave {
+                Cave::Start | Cave::End => "doublecircle",
+                Cave::Big => "box",
+                Cave::Little => "ellipse",
+            };
+            dot.node(name, &[("shape", shape)]);
+        }
+        for (from, paths) in self.paths.iter().enumerate() {
+            for to in paths.iter().copied().filter(|to| *to > from) {
+                dot.edge(&self.names[from], &self.names[to], &[]);
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day12 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         fn get_name(
@@ -110,6 +133,11 @@ impl Runner for Day12 {
             self.paths[from].push(to);
             self.paths[to].push(from);
         }
+
+        self.names = vec![String::new(); names.len()];
+        for (name, idx) in names {
+            self.names[idx] = name;
+        }
         Ok(())
     }
 
@@ -125,6 +125,6 @@ impl Day13 {
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
         self.fold(false);
-        Ok(self.display().into())
+        Ok(common::ocr::recognize(&self.display())?.into())
     }
 }
@@ -1,3 +1,4 @@
+use common::cursor::Cursor;
 use std::str::FromStr;
 
 #[allow(unused_imports)]
@@ -22,84 +23,28 @@ impl FromStr for Number {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_number(mut chars: &[char]) -> (usize, &[char]) {
-            let mut v = 0;
-            loop {
-                match chars[0] {
-                    '0'..='9' => {
-                        v = (v * 10) + ((chars[0] as u8 - b'0') as usize);
-                        chars = &chars[1..];
-                    }
-                    _ => return (v, chars),
-                }
-            }
-        }
-
-        fn parse(mut chars: &[char]) -> Result<(Number, &[char]), Error> {
-            // Consume [
-            if chars[0] != '[' {
-                return Err(Error::InvalidInput(format!(
-                    "Expected '[', Found {:?}",
-                    chars.iter().collect::<String>()
-                )));
-            }
-            chars = &chars[1..];
-
-            let a: Number;
-            let b: Number;
-
-            // Parse first number
-            match chars[0] {
-                '[' => (a, chars) = parse(chars)?,
-                _ => {
-                    let n: usize;
-                    (n, chars) = parse_number(chars);
-                    a = Number::Literal(n);
-                }
-            }
-
-            // Consume ,
-            if chars[0] != ',' {
-                return Err(Error::InvalidInput(format!(
-                    "Expected ',', Found {:?}",
-                    chars.iter().collect::<String>()
-                )));
-            }
-            chars = &chars[1..];
-
-            // Parse second number
-            match chars[0] {
-                '[' => (b, chars) = parse(chars)?,
-                _ => {
-                    let n: usize;
-                    (n, chars) = parse_number(chars);
-                    b = Number::Literal(n);
-                }
-            }
-
-            // Consume ]
-            if chars[0] != ']' {
-                return Err(Error::InvalidInput(format!(
-                    "Expected ']', Found {:?}",
-                    chars.iter().collect::<String>()
-                )));
-            }
-            chars = &chars[1..];
-
-            Ok((Number::Pair(Box::new(a), Box::new(b)), chars))
+        let mut cursor = Cursor::new(s);
+        if cursor.peek() != Some(b'[') {
+            return Err(cursor.error("Expected a pair"));
         }
-
-        let chars: Vec<char> = s.chars().collect();
-        let (pair, chars) = parse(&chars)?;
-        if !chars.is_empty() {
-            return Err(Error::InvalidInput(chars.iter().collect()));
-        }
-
+        let pair = Number::parse(&mut cursor)?;
+        cursor.finish()?;
         Ok(pair)
     }
 }
 
 impl Number {
+    fn parse(cursor: &mut Cursor) -> Result<Number, Error> {
+        if !cursor.eat(b'[') {
+            return Ok(Number::Literal(cursor.unsigned()?));
+        }
+        let a = Number::parse(cursor)?;
+        cursor.expect(b',')?;
+        let b = Number::parse(cursor)?;
+        cursor.expect(b']')?;
+        Ok(Number::Pair(Box::new(a), Box::new(b)))
+    }
+
     fn magnitude(&self) -> usize {
         match self {
             Self::Literal(n) => *n,
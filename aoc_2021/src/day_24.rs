@@ -1,91 +1,54 @@
+use asm::{Args, Effect, InstructionSet, Machine, Value};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Output, RunOutput, Runner};
 
-#[derive(Clone, Debug)]
-#[allow(dead_code)]
-struct Reg(usize);
-
-#[derive(Clone, Debug)]
-enum Value {
-    Reg(usize),
-    Imm(isize),
+#[derive(Copy, Clone, Debug)]
+enum Op {
+    Inp(usize),
+    Add(usize, Value),
+    Mul(usize, Value),
+    Div(usize, Value),
+    Mod(usize, Value),
+    Eql(usize, Value),
 }
 
-impl TryFrom<&str> for Reg {
-    type Error = Error;
-
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let v: Value = s.try_into()?;
-        if let Value::Reg(r) = v {
-            return Ok(Reg(r));
+impl InstructionSet for Op {
+    const REGISTERS: &'static [&'static str] = &["w", "x", "y", "z"];
+
+    fn parse(op: &str, args: &Args) -> Result<Self, Error> {
+        match op {
+            "inp" => Ok(Self::Inp(args.reg(0)?)),
+            "add" => Ok(Self::Add(args.reg(0)?, args.value(1)?)),
+            "mul" => Ok(Self::Mul(args.reg(0)?, args.value(1)?)),
+            "div" => Ok(Self::Div(args.reg(0)?, args.value(1)?)),
+            "mod" => Ok(Self::Mod(args.reg(0)?, args.value(1)?)),
+            "eql" => Ok(Self::Eql(args.reg(0)?, args.value(1)?)),
+            _ => Err(args.invalid()),
         }
-        Err(Error::InvalidInput(format!("Not a Reg: {s:?}")))
     }
-}
 
-impl Value {
-    fn imm(&self) -> isize {
+    fn execute(self, machine: &mut Machine<Self>) -> Effect {
+        let registers = &mut machine.registers;
         match self {
-            Self::Imm(v) => *v,
-            _ => panic!(),
-        }
-    }
-}
-
-impl TryFrom<&str> for Value {
-    type Error = Error;
-
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        if let Ok(num) = s.parse::<isize>() {
-            Ok(Self::Imm(num))
-        } else if s.len() == 1 {
-            let r = s.chars().nth(0).unwrap();
-            if !('w'..='z').contains(&r) {
-                Err(Error::InvalidInput(format!("Not a Value: {s:?}")))
-            } else {
-                Ok(Self::Reg((r as u8 - b'w') as usize))
-            }
-        } else {
-            Err(Error::InvalidInput(format!("Not a Value: {s:?}")))
-        }
-    }
-}
-
-#[derive(Clone, Debug)]
-#[allow(dead_code)]
-enum Op {
-    Inp(Reg),
-    Add(Reg, Value),
-    Mul(Reg, Value),
-    Div(Reg, Value),
-    Mod(Reg, Value),
-    Eql(Reg, Value),
-}
-
-impl TryFrom<&str> for Op {
-    type Error = Error;
-
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let parts: Vec<&str> = s.split(" ").collect();
-        match parts[0] {
-            "inp" => Ok(Self::Inp(parts[1].try_into()?)),
-            "add" => Ok(Self::Add(parts[1].try_into()?, parts[2].try_into()?)),
-            "mul" => Ok(Self::Mul(parts[1].try_into()?, parts[2].try_into()?)),
-            "div" => Ok(Self::Div(parts[1].try_into()?, parts[2].try_into()?)),
-            "mod" => Ok(Self::Mod(parts[1].try_into()?, parts[2].try_into()?)),
-            "eql" => Ok(Self::Eql(parts[1].try_into()?, parts[2].try_into()?)),
-            _ => Err(Error::InvalidInput(format!("Not an Op: {s:?}"))),
+            Self::Inp(a) => return Effect::Input(a),
+            Self::Add(a, b) => registers[a] += b.value(registers),
+            Self::Mul(a, b) => registers[a] *= b.value(registers),
+            Self::Div(a, b) => registers[a] /= b.value(registers),
+            Self::Mod(a, b) => registers[a] %= b.value(registers),
+            Self::Eql(a, b) => registers[a] = (registers[a] == b.value(registers)) as isize,
         }
+        Effect::Next
     }
 }
 
+#[derive(Default)]
 pub struct Day24 {
-    ops: Vec<Op>,
+    alu: Machine<Op>,
 }
 
 impl Day24 {
     pub fn new() -> Self {
-        Self { ops: Vec::new() }
+        Self::default()
     }
 
     fn calculate_limits(&self) -> (usize, usize) {
@@ -93,7 +56,7 @@ impl Day24 {
 
         fn add_const(op: &Op) -> isize {
             match op {
-                Op::Add(_, b) => b.imm(),
+                Op::Add(_, Value::Imm(b)) => *b,
                 _ => panic!("NOT RIGHT"),
             }
         }
@@ -102,8 +65,8 @@ impl Day24 {
 
         for i in 0..14 {
             let idx = i * 18;
-            let push_pop = &self.ops[idx + 5];
-            let constant = &self.ops[idx + 15];
+            let push_pop = &self.alu.program[idx + 5];
+            let constant = &self.alu.program[idx + 15];
 
             let push_pop = add_const(push_pop);
             let constant = add_const(constant);
@@ -158,10 +121,7 @@ impl Day24 {
 impl Runner for Day24 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
-        for line in lines.iter() {
-            let op: Op = line.try_into()?;
-            self.ops.push(op);
-        }
+        self.alu = Machine::parse(lines.iter())?;
         Ok(())
     }
 
@@ -1,3 +1,4 @@
+use common::dot::{dump, NewDump};
 use helper::NewRunner;
 use std::collections::BTreeMap;
 
@@ -26,6 +27,7 @@ mod day_22;
 mod day_23;
 mod day_24;
 mod day_25;
+pub mod bits;
 
 pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2021, 1), (2, || Box::new(day_01::Day01::new())));
@@ -53,4 +55,8 @@ pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2021, 23), (2, || Box::new(day_23::Day23::new())));
     runners.insert((2021, 24), (2, || Box::new(day_24::Day24::new())));
     runners.insert((2021, 25), (1, || Box::new(day_25::Day25::new())));
-}
\ No newline at end of file
+}
+
+pub fn register_dumps(dumps: &mut BTreeMap<(usize, usize), NewDump>) {
+    dumps.insert((2021, 12), |input| dump(day_12::Day12::new(), input));
+}
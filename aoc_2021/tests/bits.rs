@@ -0,0 +1,233 @@
+use aoc_2021::bits::{BitsError, LengthType, Op, Packet};
+
+// Hex for a transmission written out as binary digits, padded to whole bytes
+fn from_binary(bits: &str) -> String {
+    let mut bits = bits.replace(' ', "");
+    while bits.len() % 8 != 0 {
+        bits.push('0');
+    }
+    bits.as_bytes()
+        .chunks(4)
+        .map(|digit| {
+            let v = digit.iter().fold(0, |v, &b| v << 1 | (b - b'0') as u32);
+            char::from_digit(v, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect()
+}
+
+fn round_trip(packet: &Packet) {
+    for length_type in [None, Some(LengthType::Bits), Some(LengthType::Packets)] {
+        let hex = packet.encode_with(length_type).unwrap();
+        assert_eq!(&Packet::decode(&hex).unwrap(), packet, "{hex}");
+    }
+}
+
+#[test]
+fn decoding_examples() {
+    assert_eq!(Packet::decode("D2FE28"), Ok(Packet::literal(6, 2021)));
+    assert_eq!(
+        Packet::decode("38006F45291200"),
+        Ok(Packet::operator(
+            1,
+            Op::LessThan,
+            vec![Packet::literal(6, 10), Packet::literal(2, 20)]
+        ))
+    );
+    assert_eq!(
+        Packet::decode("EE00D40C823060"),
+        Ok(Packet::operator(
+            7,
+            Op::Max,
+            vec![
+                Packet::literal(2, 1),
+                Packet::literal(4, 2),
+                Packet::literal(1, 3)
+            ]
+        ))
+    );
+
+    for (hex, version_sum) in [
+        ("8A004A801A8002F478", 16),
+        ("620080001611562C8802118E34", 12),
+        ("C0015000016115A2E0802F182340", 23),
+        ("A0016C880162017C3686B18A3D4780", 31),
+    ] {
+        let packet = Packet::decode(hex).unwrap();
+        assert_eq!(packet.version_sum(), version_sum, "{hex}");
+        round_trip(&packet);
+    }
+}
+
+#[test]
+fn evaluating_examples() {
+    for (hex, value) in [
+        ("C200B40A82", 3),
+        ("04005AC33890", 54),
+        ("880086C3E88112", 7),
+        ("CE00C43D881120", 9),
+        ("D8005AC2A8F0", 1),
+        ("F600BC2D8F", 0),
+        ("9C005AC2F8F0", 0),
+        ("9C0141080250320F1802104A08", 1),
+    ] {
+        let packet = Packet::decode(hex).unwrap();
+        assert_eq!(packet.eval(), Ok(value), "{hex}");
+        round_trip(&packet);
+    }
+}
+
+#[test]
+fn encoding_chooses_length_types() {
+    let less = Packet::decode("38006F45291200").unwrap();
+    assert_eq!(
+        less.encode_with(Some(LengthType::Bits)).unwrap(),
+        "38006F45291200"
+    );
+    assert_eq!(less.encode().unwrap(), "3A00B4529120");
+    let max = Packet::decode("EE00D40C823060").unwrap();
+    assert_eq!(max.encode().unwrap(), "EE00D40C823060");
+    assert_eq!(Packet::literal(6, 2021).encode().unwrap(), "D2FE28");
+    assert_eq!(Packet::literal(0, 0).encode().unwrap(), "1000");
+
+    let many = Packet::operator(0, Op::Sum, vec![Packet::literal(0, 1); 2048]);
+    assert_eq!(
+        many.encode_with(Some(LengthType::Packets)),
+        Err(BitsError::TooManyPackets(2048))
+    );
+    // Too many to count, so given by their 2048 * 11 bits
+    assert!(many.encode().unwrap().starts_with("0160"));
+    assert_eq!(Packet::decode(&many.encode().unwrap()).unwrap(), many);
+    round_trip(&Packet::operator(
+        0,
+        Op::Sum,
+        vec![Packet::literal(0, 1); 2047],
+    ));
+
+    let too_many = Packet::operator(0, Op::Sum, vec![Packet::literal(0, 1); 3000]);
+    assert_eq!(
+        too_many.encode_with(Some(LengthType::Bits)),
+        Err(BitsError::TooManyBits(33000))
+    );
+    assert_eq!(
+        Packet::literal(8, 1).encode(),
+        Err(BitsError::InvalidVersion(8))
+    );
+}
+
+#[test]
+fn round_trips_generated_trees() {
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move |n: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % n
+    };
+    let ops = [
+        Op::Sum,
+        Op::Product,
+        Op::Min,
+        Op::Max,
+        Op::GreaterThan,
+        Op::LessThan,
+        Op::EqualTo,
+    ];
+    fn generate(next: &mut impl FnMut(u64) -> u64, ops: &[Op], depth: usize) -> Packet {
+        let version = next(8) as u8;
+        if depth == 0 || next(3) == 0 {
+            let value = match next(3) {
+                0 => next(16),
+                1 => next(1 << 20),
+                _ => next(u64::MAX),
+            };
+            return Packet::literal(version, value);
+        }
+        let op = ops[next(ops.len() as u64) as usize];
+        let count = next(5) as usize;
+        let packets = (0..count).map(|_| generate(next, ops, depth - 1)).collect();
+        Packet::operator(version, op, packets)
+    }
+
+    for _ in 0..200 {
+        round_trip(&generate(&mut next, &ops, 4));
+    }
+}
+
+#[test]
+fn reporting_bad_transmissions() {
+    assert_eq!(
+        Packet::decode("D2XE28"),
+        Err(BitsError::InvalidHex { at: 8, c: 'X' })
+    );
+    assert_eq!(Packet::decode("D2FE"), Err(BitsError::Truncated { at: 16 }));
+    assert_eq!(Packet::decode(""), Err(BitsError::Truncated { at: 0 }));
+    // An operator counting two packets with only one after it
+    assert_eq!(
+        Packet::decode(&from_binary("000 000 1 00000000010 000 100 00001")),
+        Err(BitsError::Truncated { at: 32 })
+    );
+    assert_eq!(
+        Packet::decode(&from_binary(&format!(
+            "000 100 {}00001",
+            "10001".repeat(16)
+        ))),
+        Err(BitsError::LiteralTooLarge { at: 0 })
+    );
+    // An operator giving 10 bits to an 11 bit literal
+    assert_eq!(
+        Packet::decode(&from_binary("000 000 0 000000000001010 000 100 00001")),
+        Err(BitsError::LengthOverrun { at: 22, len: 10 })
+    );
+
+    assert_eq!(
+        Packet::operator(0, Op::Min, vec![]).eval(),
+        Err(BitsError::WrongOperands {
+            op: Op::Min,
+            count: 0
+        })
+    );
+    assert_eq!(
+        Packet::operator(0, Op::EqualTo, vec![Packet::literal(0, 1)]).eval(),
+        Err(BitsError::WrongOperands {
+            op: Op::EqualTo,
+            count: 1
+        })
+    );
+    assert_eq!(
+        Packet::operator(
+            0,
+            Op::Product,
+            vec![Packet::literal(0, 1 << 40), Packet::literal(0, 1 << 40)]
+        )
+        .eval(),
+        Err(BitsError::Overflow(Op::Product))
+    );
+}
+
+#[test]
+fn printing_s_expressions() {
+    let packet = Packet::operator(
+        3,
+        Op::Sum,
+        vec![
+            Packet::literal(1, 5),
+            Packet::operator(
+                0,
+                Op::LessThan,
+                vec![Packet::literal(6, 7), Packet::literal(2, 9)],
+            ),
+        ],
+    );
+    assert_eq!(
+        packet.to_string(),
+        "(sum v3 (lit v1 5) (lt v0 (lit v6 7) (lit v2 9)))"
+    );
+    assert_eq!(
+        format!("{packet:#}"),
+        "(sum v3\n  (lit v1 5)\n  (lt v0\n    (lit v6 7)\n    (lit v2 9)))"
+    );
+    assert_eq!(
+        Packet::decode("C200B40A82").unwrap().to_string(),
+        "(sum v6 (lit v6 1) (lit v2 2))"
+    );
+}
@@ -0,0 +1 @@
+common::sample_tests!(aoc_2021::register, 2021);
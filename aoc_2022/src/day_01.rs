@@ -48,6 +48,9 @@ impl Runner for Day01 {
                 elf.push(item);
             }
         }
+        if elf.total != 0 {
+            self.elves.push(elf);
+        }
         Ok(())
     }
 
@@ -76,6 +76,6 @@ impl Day10 {
                 result.push('\n');
             }
         }
-        Ok(result.into())
+        Ok(common::ocr::recognize(&result)?.into())
     }
 }
@@ -1,5 +1,4 @@
-use std::collections::VecDeque;
-
+use common::search::{Search, SearchState};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -26,31 +25,31 @@ impl Day12 {
             end: usize::MAX,
         }
     }
+}
 
-    fn walk<F>(&self, f: F) -> usize
-    where
-        F: Fn(usize) -> bool,
-    {
-        let mut best: Vec<Option<u32>> = self.graph.iter().map(|_| None).collect();
-        best[self.end] = Some(0);
-        let mut work: VecDeque<usize> = VecDeque::new();
-        work.push_front(self.end);
+// Walking backwards down from the end, towards either the start or any of the lowest squares
+#[derive(Copy, Clone, Debug)]
+struct Square {
+    pos: usize,
+    to: Option<usize>,
+}
 
-        while let Some(pos) = work.pop_front() {
-            let steps = best[pos].unwrap() + 1;
-            for npos in self.graph[pos].1.iter() {
-                if best[*npos].is_none() {
-                    // Check if at level 0 and at exit condition
-                    if self.graph[*npos].0 == 0 && f(*npos) {
-                        return steps as usize;
-                    }
+impl SearchState for Square {
+    type Context = Day12;
+    type Key = usize;
 
-                    best[*npos] = Some(steps);
-                    work.push_back(*npos);
-                }
-            }
+    fn key(&self) -> Self::Key {
+        self.pos
+    }
+
+    fn neighbors(&self, day: &mut Day12, out: &mut Vec<(Self, usize)>) {
+        for pos in day.graph[self.pos].1.iter() {
+            out.push((Self { pos: *pos, ..*self }, 1));
         }
-        0
+    }
+
+    fn is_goal(&self, day: &Day12) -> bool {
+        day.graph[self.pos].0 == 0 && (self.to.is_none() || self.to == Some(self.pos))
     }
 }
 
@@ -132,10 +131,18 @@ impl Runner for Day12 {
 
 impl Day12 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.walk(|pos| pos == self.start).into())
+        let end = Square {
+            pos: self.end,
+            to: Some(self.start),
+        };
+        Ok(Search::new(self).bfs(end).cost()?.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.walk(|_| true).into())
+        let end = Square {
+            pos: self.end,
+            to: None,
+        };
+        Ok(Search::new(self).bfs(end).cost()?.into())
     }
 }
@@ -1,6 +1,7 @@
+use common::cursor::Cursor;
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
-use std::cmp::Ordering;
+use std::{cmp::Ordering, str::FromStr};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum Packet {
@@ -9,47 +10,32 @@ enum Packet {
 }
 
 impl Packet {
-    fn new(line: &str) -> Packet {
-        fn decode(chars: &[char], s: usize) -> (Packet, usize) {
-            match chars[s] {
-                '[' => {
-                    if chars[s + 1] == ']' {
-                        return (Packet::List(Vec::new()), s + 2);
-                    }
-                    let mut list = Vec::new();
-                    let mut p = s + 1;
-                    while p < chars.len() {
-                        let (elem, e) = decode(chars, p);
-                        list.push(elem);
-                        p = e;
-                        match chars[p] {
-                            ',' => p += 1,
-                            ']' => return (Packet::List(list), p + 1),
-                            _ => unreachable!("{}", chars[p]),
-                        }
-                    }
-                    unreachable!();
-                }
-                '0'..='9' => {
-                    let mut num: u32 = chars[s] as u32 - '0' as u32;
-                    let mut s = s + 1;
-                    while chars[s] >= '0' && chars[s] <= '9' {
-                        num *= 10;
-                        num += chars[s] as u32 - '0' as u32;
-                        s += 1;
-                    }
-                    (Packet::Num(num), s)
+    fn parse(cursor: &mut Cursor) -> Result<Packet, Error> {
+        if !cursor.eat(b'[') {
+            return Ok(Packet::Num(cursor.unsigned()?));
+        }
+        let mut list = Vec::new();
+        if !cursor.eat(b']') {
+            loop {
+                list.push(Packet::parse(cursor)?);
+                if cursor.eat(b']') {
+                    break;
                 }
-                _ => unreachable!(),
+                cursor.expect(b',')?;
             }
         }
+        Ok(Packet::List(list))
+    }
+}
 
-        let chars: Vec<char> = line.chars().collect();
-        let (packet, end) = decode(&chars, 0);
-        if end != chars.len() {
-            panic!("Did not decode entire packet");
-        }
-        packet
+impl FromStr for Packet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let packet = Packet::parse(&mut cursor)?;
+        cursor.finish()?;
+        Ok(packet)
     }
 }
 
@@ -111,7 +97,9 @@ impl Day13 {
 impl Runner for Day13 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::REMOVE_EMPTY)?;
-        self.packets.extend(lines.iter().map(Packet::new));
+        for line in lines.iter() {
+            self.packets.push(line.parse()?);
+        }
         Ok(())
     }
 
@@ -141,8 +129,8 @@ impl Day13 {
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let d2 = Packet::new("[[2]]");
-        let d6 = Packet::new("[[6]]");
+        let d2: Packet = "[[2]]".parse()?;
+        let d6: Packet = "[[6]]".parse()?;
         self.packets.push(d2.clone());
         self.packets.push(d6.clone());
         self.packets.sort();
@@ -1,3 +1,4 @@
+use common::frames::{self, Frame, BLACK, GREY, YELLOW};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -16,7 +17,27 @@ impl Day14 {
         }
     }
 
+    // Rock grey and sand yellow, which needs the rock as it was before any sand fell
+    fn frame(&self, rock: &[Vec<bool>]) -> Frame {
+        let mut frame = Frame::new(self.grid[0].len(), self.grid.len(), BLACK);
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, filled) in row.iter().enumerate() {
+                if rock[y][x] {
+                    frame.set(x, y, GREY);
+                } else if *filled {
+                    frame.set(x, y, YELLOW);
+                }
+            }
+        }
+        frame
+    }
+
     fn solve<const HAS_FLOOR: bool>(&mut self) -> usize {
+        let rock = if frames::enabled() {
+            self.grid.clone()
+        } else {
+            Vec::new()
+        };
         let mut ans = 0;
         'run_loop: loop {
             ans += 1;
@@ -57,6 +78,7 @@ impl Day14 {
             }
             // self.x.0 = self.x.0.min(sand.0);
             // self.x.1 = self.x.1.max(sand.1);
+            frames::record(|| self.frame(&rock));
         }
         ans
     }
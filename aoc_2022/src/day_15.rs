@@ -1,3 +1,4 @@
+use common::interval::IntervalSet;
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -102,7 +103,7 @@ impl Runner for Day15 {
 impl Day15 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         let row = if self.sensors[0].y < 100 { 10 } else { 2000000 };
-        let mut seen: Vec<(isize, isize)> = Vec::new();
+        let mut seen = IntervalSet::new();
 
         let mut beacons = Vec::new();
         for sensor in self.sensors.iter() {
@@ -113,22 +114,11 @@ impl Day15 {
                 continue;
             }
             let dx = sensor.d - (sensor.y - row).abs();
-            seen.push((sensor.x - dx, sensor.x + dx));
+            seen.insert(sensor.x - dx..=sensor.x + dx);
         }
-        seen.sort();
         beacons.sort();
         beacons.dedup();
-        let mut last = isize::MIN;
-        let mut ans = -(beacons.len() as isize);
-        for &(from, to) in seen.iter() {
-            if to <= last {
-                continue;
-            }
-            let from = if from <= last { last + 1 } else { from };
-            let to_add = to - from + 1;
-            ans += to_add;
-            last = to;
-        }
+        let ans = seen.len() as usize - beacons.len();
         Ok(ans.into())
     }
 
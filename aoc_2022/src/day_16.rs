@@ -1,3 +1,4 @@
+use common::dot::{Dot, GraphDump};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 use std::collections::{BTreeMap, VecDeque};
@@ -85,6 +86,29 @@ impl Day16 {
     }
 }
 
+impl GraphDump for Day16 {
+    fn dot(&self) -> Dot {
+        let mut dot = Dot::graph("valves");
+        for (name, room) in self.names.iter().zip(self.rooms.iter()) {
+            if room.flow_rate == 0 {
+                dot.node(name, &[]);
+            } else {
+                let label = format!("{name}\n{}", room.flow_rate);
+                dot.node(name, &[("label", &label), ("style", "bold")]);
+            }
+        }
+        for (from, room) in self.rooms.iter().enumerate() {
+            // Tunnels are listed from both ends
+            for to in room.exits.iter().copied() {
+                if to > from || !self.rooms[to].exits.contains(&from) {
+                    dot.edge(&self.names[from], &self.names[to], &[]);
+                }
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day16 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
@@ -1,3 +1,7 @@
+use common::{
+    cycle::Detector,
+    frames::{self, Frame, BLACK, GREY, YELLOW},
+};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -18,6 +22,13 @@ const BLOCKS: &[&[u8]] = &[
     &[0b0011000, 0b0011000],
 ];
 
+// Rows at the top of the stack which, with the next block and jet, stand in for the whole
+// state when looking for a cycle, which is far deeper than any block gets
+const TOP_ROWS: usize = 64;
+
+// Rows of the stack shown in each frame
+const FRAME_ROWS: usize = 48;
+
 enum Dir {
     Left,
     Right,
@@ -49,6 +60,28 @@ impl Day17 {
         println!();
     }
 
+    // The top of the stack with the block which just landed at `bottom` in yellow
+    fn frame(&self, bottom: usize, block: &[u8]) -> Frame {
+        let mut frame = Frame::new(7, FRAME_ROWS, BLACK);
+        let top = self.stack.len();
+        for (y, row) in (top.saturating_sub(FRAME_ROWS)..top).rev().enumerate() {
+            let landed = row
+                .checked_sub(bottom)
+                .and_then(|i| block.get(i))
+                .copied()
+                .unwrap_or(0);
+            for x in 0..7 {
+                let bit = 1 << (6 - x);
+                if landed & bit != 0 {
+                    frame.set(x, y, YELLOW);
+                } else if self.stack[row] & bit != 0 {
+                    frame.set(x, y, GREY);
+                }
+            }
+        }
+        frame
+    }
+
     fn gust(&mut self, bottom: usize, block: &mut [u8]) {
         match self.jets[self.tick % self.jets.len()] {
             Dir::Left => {
@@ -116,48 +149,31 @@ impl Day17 {
                 break;
             }
         }
+        frames::record(|| self.frame(bottom, &block));
     }
 
-    fn simulate(&mut self, total_drops: usize) -> usize {
-        let mut heights: Vec<usize> = Vec::new();
+    fn simulate(&mut self, total_drops: usize) -> Result<usize, Error> {
+        let mut detector = Detector::new();
+        // The height after each number of drops
+        let mut heights = vec![0];
         for drop in 0..total_drops {
+            let top = self.stack.len().saturating_sub(TOP_ROWS);
+            let key = (
+                drop % BLOCKS.len(),
+                self.tick % self.jets.len(),
+                self.stack[top..].to_vec(),
+            );
+            if let Some(cycle) = detector.push(key) {
+                return cycle.extrapolate(total_drops, &heights);
+            }
+
             self.drop_block(BLOCKS[drop % BLOCKS.len()]);
             heights.push(self.stack.len());
 
-            if drop >= 4096 {
-                const CYCLE_COUNT: usize = 2;
-                'cycle: for cycle in 256..(drop / (CYCLE_COUNT + 1)) {
-                    let delta = heights[drop] - heights[drop - cycle];
-                    for i in 0..=cycle {
-                        for c in 0..CYCLE_COUNT {
-                            if heights[drop - i] - heights[drop - ((c + 1) * cycle) - i]
-                                != (c + 1) * delta
-                            {
-                                continue 'cycle;
-                            }
-                        }
-                    }
-                    println!("{drop} {cycle}");
-                    let cycle_diff = heights[drop] - heights[drop - cycle];
-                    let rocks_left = total_drops - drop;
-                    let cycles = rocks_left / cycle;
-                    let left_over = total_drops - drop - (cycles * cycle);
-
-                    let left_over_height =
-                        heights[drop - cycle + left_over - 1] - heights[drop - cycle];
-
-                    let ans = heights[drop] + (cycles * cycle_diff) + left_over_height;
-
-                    println!("{cycle_diff} {rocks_left} {cycles} {left_over} {left_over_height}");
-                    println!("ans: {ans}");
-                    return ans;
-                }
-            }
-
             // self._print(&self.stack);
         }
 
-        self.stack.len()
+        Ok(self.stack.len())
     }
 }
 
@@ -185,11 +201,11 @@ impl Runner for Day17 {
 impl Day17 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         // Ok(self.simulate(4).into())
-        Ok(self.simulate(2022).into())
+        Ok(self.simulate(2022)?.into())
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        Ok(self.simulate(1000000000000).into())
+        Ok(self.simulate(1000000000000)?.into())
         // Ok(0.into())
     }
 }
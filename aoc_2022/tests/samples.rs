@@ -0,0 +1 @@
+common::sample_tests!(aoc_2022::register, 2022);
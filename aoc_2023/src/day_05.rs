@@ -1,5 +1,7 @@
+use common::interval::IntervalSet;
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
+use std::ops::Range;
 
 #[derive(Debug)]
 pub enum RunnerError {}
@@ -10,113 +12,13 @@ impl From<RunnerError> for Error {
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
-struct MyRange(std::ops::Range<isize>);
-
-impl PartialOrd for MyRange {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for MyRange {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.start.cmp(&other.start) {
-            std::cmp::Ordering::Equal => self.end.cmp(&other.end),
-            x => x,
-        }
-    }
-}
-
-impl std::fmt::Debug for MyRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
-    }
-}
-
-impl MyRange {
-    fn add(&mut self, delta: isize) {
-        self.start += delta;
-        self.end += delta;
-    }
-
-    fn overlaps_with(&self, other: &Self) -> Option<Self> {
-        let start = self.start.max(other.start);
-        let end = self.end.min(other.end);
-
-        // If end > start then we found an overlap!
-        if end > start {
-            Some(MyRange(start..end))
-        } else {
-            None
-        }
-    }
-
-    fn remove_overlap(&mut self, overlap: &Self) -> Option<Self> {
-        assert!(self.start <= overlap.start);
-        assert!(self.end >= overlap.end);
-
-        let mut tail = None;
-        if self.0 == (overlap.start..overlap.end) {
-            // Whole cur_remaining is consumed
-            self.end = self.start
-        } else if self.start < overlap.start && self.end > overlap.end {
-            // Remove gap in the middle of cur_remaining
-            tail = Some(Self(overlap.end..self.end));
-            self.end = overlap.start;
-        } else if self.start < overlap.start {
-            // Remove the end of cur_remaining
-            self.end = overlap.start;
-        } else if self.end > overlap.end {
-            // Remove the beginning of cur_remaining
-            self.start = overlap.end;
-        } else {
-            // Should not ever get here
-            unreachable!();
-        }
-
-        tail
-    }
-}
-
-impl std::ops::Deref for MyRange {
-    type Target = std::ops::Range<isize>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl std::ops::DerefMut for MyRange {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
-
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Map {
-    src: MyRange,
-    delta: isize,
-}
-
-impl std::ops::Deref for Map {
-    type Target = MyRange;
-
-    fn deref(&self) -> &Self::Target {
-        &self.src
-    }
-}
-
-impl std::ops::DerefMut for Map {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.src
-    }
-}
+// The source ranges of one map and how far each moves the values in it
+type Layer = Vec<(Range<isize>, i128)>;
 
 #[derive(Default)]
 pub struct Day05 {
     seeds: Vec<isize>,
-    ranges: Vec<Map>,
+    layers: Vec<Layer>,
 }
 
 impl Day05 {
@@ -124,101 +26,44 @@ impl Day05 {
         Self::default()
     }
 
-    fn find_best_location(&self, range: MyRange) -> isize {
-        let mut best_location = isize::MAX;
-        let mut search = vec![range];
-
-        while let Some(mut search_range) = search.pop() {
-            for range in self.ranges.iter() {
-                if let Some(overlap) = range.overlaps_with(&search_range) {
-                    best_location = best_location.min(overlap.start + range.delta);
-                    if let Some(tail) = search_range.remove_overlap(&overlap) {
-                        search.push(tail);
-                    }
-                }
-            }
-
-            if search_range.start != search_range.end {
-                best_location = best_location.min(search_range.start);
-            }
+    fn best_location(&self, seeds: IntervalSet<isize>) -> Result<RunOutput, Error> {
+        let locations = self.layers.iter().fold(seeds, |values, layer| {
+            values.map_offsets(layer.iter().cloned())
+        });
+        match locations.first() {
+            Some(location) => Ok(location.into()),
+            None => Err(Error::Unsolved),
         }
-
-        best_location
     }
 }
 
 impl Runner for Day05 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let lines = Lines::from_bufread(file, LinesOpt::TRIM | LinesOpt::REMOVE_EMPTY)?;
-        let lines: Vec<&str> = lines.iter().collect();
-        self.seeds.extend(
-            lines[0]
-                .split_whitespace()
-                .skip(1)
-                .map(|s| s.parse::<isize>().unwrap()),
-        );
+        let Some(seeds) = lines.first() else {
+            return Err(Error::InvalidInput("No seeds".into()));
+        };
+        for seed in seeds.split_whitespace().skip(1) {
+            self.seeds.push(seed.parse()?);
+        }
 
-        // While building the ranges, all of the ranges in self.ranges are in destination ranges.
-        // This lets you match a current rule with the existing merged ranges.
-        //
-        // ie.  If you map 50..52 to 30.32 in the first set of rules, you would then be looking for
-        // something in the 30..32 range for the second set of rules.  After all of the rule sets
-        // are merged in to one set of rules (with all of the accumulated deltas summed up),
-        // convert the ranges back in to source ranges.
-        let mut next_ranges = Vec::new();
         for line in lines.iter().skip(1) {
             if line.ends_with(" map:") {
-                self.ranges.append(&mut next_ranges);
-            } else {
-                let parts = line.split_whitespace().collect::<Vec<&str>>();
-                assert_eq!(parts.len(), 3);
-                let dest: isize = parts[0].parse().unwrap();
-                let src: isize = parts[1].parse().unwrap();
-                let len: isize = parts[2].parse().unwrap();
-                let delta = dest - src;
-                let src = MyRange(src..src + len);
-                let mut cur_ranges = vec![Map { src, delta }];
-                let mut ii = 0;
-                'search_loop: while ii < cur_ranges.len() {
-                    let mut jj = 0;
-                    while jj < self.ranges.len() {
-                        // Does cur_ranges[ii] overlap with self.ranges[jj] ?
-                        if let Some(mut overlap) = cur_ranges[ii].overlaps_with(&self.ranges[jj]) {
-                            if let Some(tail) = cur_ranges[ii].remove_overlap(&overlap) {
-                                cur_ranges.push(Map { src: tail, delta });
-                            }
-                            if let Some(tail) = self.ranges[jj].remove_overlap(&overlap) {
-                                self.ranges.push(Map {
-                                    src: tail,
-                                    delta: self.ranges[jj].delta,
-                                });
-                            }
-
-                            overlap.add(delta);
-                            let overlap = Map {
-                                src: overlap,
-                                delta: delta + self.ranges[jj].delta,
-                            };
-                            next_ranges.push(overlap);
-                            continue 'search_loop;
-                        }
-                        jj += 1;
-                    }
-                    ii += 1;
-                }
-                cur_ranges.retain(|m| m.start != m.end);
-                cur_ranges.iter_mut().for_each(|map| map.src.add(map.delta));
-                next_ranges.extend(cur_ranges);
+                self.layers.push(Vec::new());
+                continue;
             }
+            let Some(layer) = self.layers.last_mut() else {
+                return Err(Error::InvalidInput(format!("Range before a map: '{line}'")));
+            };
+            let parts = line.split_whitespace().collect::<Vec<&str>>();
+            if parts.len() != 3 {
+                return Err(Error::InvalidInput(format!("Range: '{line}'")));
+            }
+            let dest: isize = parts[0].parse()?;
+            let src: isize = parts[1].parse()?;
+            let len: isize = parts[2].parse()?;
+            layer.push((src..src + len, (dest - src) as i128));
         }
-        self.ranges.append(&mut next_ranges);
-        self.ranges.retain(|m| m.start != m.end);
-
-        // Convert all ranges from dest to source
-        self.ranges
-            .iter_mut()
-            .for_each(|map| map.src.add(-map.delta));
-        self.ranges.sort();
 
         Ok(())
     }
@@ -234,26 +79,16 @@ impl Runner for Day05 {
 
 impl Day05 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
-        let mut best_location = isize::MAX;
-        for seed in self.seeds.iter().copied() {
-            let location = self.find_best_location(MyRange(seed..seed + 1));
-            if location < best_location {
-                best_location = location;
-            }
-            println!("{seed} => {location}");
-        }
-        Ok(best_location.into())
+        let seeds = self.seeds.iter().map(|&seed| seed..=seed).collect();
+        self.best_location(seeds)
     }
 
     fn part2(&mut self) -> Result<RunOutput, Error> {
-        let mut best_location = isize::MAX;
-        for seed in self.seeds.chunks(2) {
-            let location = self.find_best_location(MyRange(seed[0]..seed[0] + seed[1]));
-            if location < best_location {
-                best_location = location;
-            }
-            println!("{seed:?} => {location}");
-        }
-        Ok(best_location.into())
+        let seeds = self
+            .seeds
+            .chunks(2)
+            .map(|seed| seed[0]..seed[0] + seed[1])
+            .collect();
+        self.best_location(seeds)
     }
 }
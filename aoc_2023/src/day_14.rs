@@ -1,6 +1,6 @@
+use common::cycle::Detector;
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
-use std::collections::HashMap;
 
 #[derive(Debug)]
 pub enum RunnerError {}
@@ -198,18 +198,21 @@ impl Day14 {
 
         self.dish._dump();
 
-        let mut seen = HashMap::new();
+        let mut detector = Detector::new();
+        detector.push(self.dish.rounded_rocks());
         for i in 1..=TOTAL_CYCLES {
             self.dish.spin();
             println!("after {i} cycles");
             self.dish._dump();
 
-            let rounded = self.dish.rounded_rocks();
-            if let Some(cycle_start) = seen.insert(rounded, i) {
-                let cycle_len = i - cycle_start;
-                let spins_remaining = TOTAL_CYCLES - i;
-                let spins_after_cycles = spins_remaining % cycle_len;
-                println!("Found a cycle from {cycle_start} to {i}, {spins_remaining} spins remaining, {spins_after_cycles} spins after the cycles complete");
+            if let Some(cycle) = detector.push(self.dish.rounded_rocks()) {
+                // Back where the cycle started, so only the spins from there to the one which
+                // ends up where the last spin would are left
+                let spins_after_cycles = cycle.fast_forward(TOTAL_CYCLES) - cycle.start;
+                println!(
+                    "Found a cycle from {} to {i}, {spins_after_cycles} spins after the cycles complete",
+                    cycle.start
+                );
                 for _ in 0..spins_after_cycles {
                     self.dish.spin();
                 }
@@ -1,3 +1,4 @@
+use common::grid::{Cell, Grid};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 
@@ -18,139 +19,131 @@ enum Optics {
     },
 }
 
+impl Optics {
+    fn is_energized(&self) -> bool {
+        matches!(
+            self,
+            Optics::Empty { energized: true }
+                | Optics::Mirror { e1: true, .. }
+                | Optics::Mirror { e2: true, .. }
+                | Optics::Splitter {
+                    energized: true,
+                    ..
+                }
+        )
+    }
+}
+
+impl Cell for Optics {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '/' | '\\' => Some(Optics::Mirror {
+                typ: c,
+                e1: false,
+                e2: false,
+            }),
+            '|' | '-' => Some(Optics::Splitter {
+                typ: c,
+                energized: false,
+                split: false,
+            }),
+            '.' => Some(Optics::Empty { energized: false }),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Optics::Empty { .. } => '.',
+            Optics::Mirror { typ, .. } | Optics::Splitter { typ, .. } => *typ,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Day16 {
-    tiles: Vec<Vec<Optics>>,
+    tiles: Grid<Optics>,
 }
 
 impl Day16 {
     pub fn new() -> Self {
-        Self { tiles: Vec::new() }
-    }
-
-    fn _dump(&self, energized: bool) {
-        for row in self.tiles.iter() {
-            for col in row.iter() {
-                print!(
-                    "{}",
-                    if energized {
-                        match col {
-                            Optics::Empty { energized: true }
-                            | Optics::Mirror {
-                                typ: _,
-                                e1: true,
-                                e2: _,
-                            }
-                            | Optics::Mirror {
-                                typ: _,
-                                e1: _,
-                                e2: true,
-                            }
-                            | Optics::Splitter {
-                                typ: _,
-                                energized: true,
-                                split: _,
-                            } => '#',
-                            _ => '.',
-                        }
-                    } else {
-                        match col {
-                            Optics::Empty { energized: _ } => '.',
-                            Optics::Mirror {
-                                typ: c,
-                                e1: _,
-                                e2: _,
-                            }
-                            | Optics::Splitter {
-                                typ: c,
-                                energized: _,
-                                split: _,
-                            } => *c,
-                        }
-                    }
-                );
-            }
-            println!();
+        Self {
+            tiles: Grid::default(),
         }
     }
 
     fn energize(&mut self, mut x: usize, mut y: usize, dx: isize, dy: isize) {
         loop {
-            if let Some(row) = self.tiles.get_mut(y) {
-                if let Some(tile) = row.get_mut(x) {
-                    match tile {
-                        Optics::Empty { energized } => *energized = true,
-                        Optics::Mirror { typ: '/', e1, e2 } => {
-                            if dx == 1 || dy == 1 {
-                                if *e1 {
-                                    return;
-                                }
-                                *e1 = true;
-                            } else {
-                                if *e2 {
-                                    return;
-                                }
-                                *e2 = true;
-                            }
-                            x = (x as isize - dy) as usize;
-                            y = (y as isize - dx) as usize;
-                            self.energize(x, y, -dy, -dx);
+            let Some(tile) = self.tiles.get_mut(x as isize, y as isize) else {
+                return;
+            };
+            match tile {
+                Optics::Empty { energized } => *energized = true,
+                Optics::Mirror { typ: '/', e1, e2 } => {
+                    if dx == 1 || dy == 1 {
+                        if *e1 {
                             return;
                         }
-                        Optics::Mirror { typ: '\\', e1, e2 } => {
-                            if dx == 1 || dy == -1 {
-                                if *e1 {
-                                    return;
-                                }
-                                *e1 = true;
-                            } else {
-                                if *e2 {
-                                    return;
-                                }
-                                *e2 = true;
-                            }
-                            x = (x as isize + dy) as usize;
-                            y = (y as isize + dx) as usize;
-                            self.energize(x, y, dy, dx);
+                        *e1 = true;
+                    } else {
+                        if *e2 {
                             return;
                         }
-                        Optics::Splitter {
-                            typ: '-',
-                            energized,
-                            split,
-                        } => {
-                            *energized = true;
-                            if dx == 0 {
-                                if !*split {
-                                    *split = true;
-                                    self.energize(x, y, -1, 0);
-                                    self.energize(x, y, 1, 0);
-                                }
-                                return;
-                            }
+                        *e2 = true;
+                    }
+                    x = (x as isize - dy) as usize;
+                    y = (y as isize - dx) as usize;
+                    self.energize(x, y, -dy, -dx);
+                    return;
+                }
+                Optics::Mirror { typ: '\\', e1, e2 } => {
+                    if dx == 1 || dy == -1 {
+                        if *e1 {
+                            return;
                         }
-                        Optics::Splitter {
-                            typ: '|',
-                            energized,
-                            split,
-                        } => {
-                            *energized = true;
-                            if dy == 0 {
-                                if !*split {
-                                    *split = true;
-                                    self.energize(x, y, 0, -1);
-                                    self.energize(x, y, 0, 1);
-                                }
-                                return;
-                            }
+                        *e1 = true;
+                    } else {
+                        if *e2 {
+                            return;
                         }
-                        _ => unreachable!(),
+                        *e2 = true;
                     }
-                } else {
+                    x = (x as isize + dy) as usize;
+                    y = (y as isize + dx) as usize;
+                    self.energize(x, y, dy, dx);
                     return;
                 }
-            } else {
-                return;
+                Optics::Splitter {
+                    typ: '-',
+                    energized,
+                    split,
+                } => {
+                    *energized = true;
+                    if dx == 0 {
+                        if !*split {
+                            *split = true;
+                            self.energize(x, y, -1, 0);
+                            self.energize(x, y, 1, 0);
+                        }
+                        return;
+                    }
+                }
+                Optics::Splitter {
+                    typ: '|',
+                    energized,
+                    split,
+                } => {
+                    *energized = true;
+                    if dy == 0 {
+                        if !*split {
+                            *split = true;
+                            self.energize(x, y, 0, -1);
+                            self.energize(x, y, 0, 1);
+                        }
+                        return;
+                    }
+                }
+                _ => unreachable!(),
             }
             x = (x as isize + dx) as usize;
             y = (y as isize + dy) as usize;
@@ -158,56 +151,17 @@ impl Day16 {
     }
 
     fn count_energized(&self) -> usize {
-        let mut ans = 0;
-        for row in self.tiles.iter() {
-            for col in row.iter() {
-                match col {
-                    Optics::Empty { energized: true }
-                    | Optics::Mirror {
-                        typ: _,
-                        e1: true,
-                        e2: _,
-                    }
-                    | Optics::Mirror {
-                        typ: _,
-                        e1: _,
-                        e2: true,
-                    }
-                    | Optics::Splitter {
-                        typ: _,
-                        energized: true,
-                        split: _,
-                    } => ans += 1,
-                    _ => {}
-                }
-            }
-        }
-        ans
+        self.tiles
+            .iter()
+            .filter(|(_, tile)| tile.is_energized())
+            .count()
     }
 }
 
 impl Runner for Day16 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
-        for line in Lines::from_bufread(file, LinesOpt::RAW)?.iter() {
-            self.tiles.push(
-                line.chars()
-                    .map(|c| match c {
-                        '/' | '\\' => Optics::Mirror {
-                            typ: c,
-                            e1: false,
-                            e2: false,
-                        },
-                        '|' | '-' => Optics::Splitter {
-                            typ: c,
-                            energized: false,
-                            split: false,
-                        },
-                        '.' => Optics::Empty { energized: false },
-                        _ => unreachable!(),
-                    })
-                    .collect(),
-            );
-        }
+        let lines = Lines::from_bufread(file, LinesOpt::RAW)?;
+        self.tiles = Grid::parse(lines.iter())?;
         Ok(())
     }
 
@@ -224,9 +178,12 @@ impl Day16 {
     fn part1(&mut self) -> Result<RunOutput, Error> {
         self.energize(0, 0, 1, 0);
 
-        self._dump(false);
-        println!();
-        self._dump(true);
+        println!("{}", self.tiles);
+        println!(
+            "{}",
+            self.tiles
+                .map(|tile| if tile.is_energized() { '#' } else { '.' })
+        );
 
         Ok(self.count_energized().into())
     }
@@ -234,8 +191,8 @@ impl Day16 {
     fn part2(&mut self) -> Result<RunOutput, Error> {
         let mut best = 0;
 
-        let width = self.tiles[0].len();
-        let height = self.tiles.len();
+        let width = self.tiles.width();
+        let height = self.tiles.height();
 
         macro_rules! run {
             ($x:expr, $y:expr, $dx:expr, $dy:expr) => {{
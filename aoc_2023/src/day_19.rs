@@ -1,8 +1,9 @@
+use common::interval::IntervalSet;
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 use std::{
     collections::BTreeMap,
-    ops::{Deref, DerefMut, Range},
+    ops::{Deref, DerefMut},
     str::FromStr,
 };
 
@@ -191,48 +192,31 @@ impl Workflow {
         let mut constraint: Constraint = constraint.clone();
         let mut ret = Vec::new();
         for rule in &self.rules {
-            match rule.condition {
+            // Split the values of the variable into those the rule matches and the rest, which
+            // carry on to the next rule
+            let (var, matched, rest) = match rule.condition {
                 Condition::LessThan(var, num) => {
-                    let range = &mut constraint[var as usize];
-                    if range.start < num {
-                        if range.end < num {
-                            // Consume whole range
-                            ret.push((rule.result.clone(), constraint));
-                            break;
-                        } else {
-                            // Consume part of the range
-                            let new_range = num..range.end;
-                            range.end = num;
-                            let mut new_constraint = constraint.clone();
-                            new_constraint[var as usize] = new_range;
-                            ret.push((rule.result.clone(), constraint));
-                            constraint = new_constraint;
-                        }
-                    }
+                    let (below, rest) = constraint[var as usize].split_at(num);
+                    (var, below, rest)
                 }
                 Condition::GreaterThan(var, num) => {
-                    let range = &mut constraint[var as usize];
-                    if range.end > num {
-                        if range.start > num {
-                            // Consume whole range
-                            ret.push((rule.result.clone(), constraint));
-                            break;
-                        } else {
-                            // Consume part of the range
-                            let new_range = range.start..num + 1;
-                            range.start = num + 1;
-                            let mut new_constraint = constraint.clone();
-                            new_constraint[var as usize] = new_range;
-                            ret.push((rule.result.clone(), constraint));
-                            constraint = new_constraint;
-                        }
-                    }
+                    let (rest, above) = constraint[var as usize].split_at(num + 1);
+                    (var, above, rest)
                 }
                 Condition::All => {
                     ret.push((rule.result.clone(), constraint));
                     break;
                 }
+            };
+            if !matched.is_empty() {
+                let mut new_constraint = constraint.clone();
+                new_constraint[var as usize] = matched;
+                ret.push((rule.result.clone(), new_constraint));
+            }
+            if rest.is_empty() {
+                break;
             }
+            constraint[var as usize] = rest;
         }
 
         ret.iter()
@@ -246,16 +230,20 @@ impl Workflow {
 }
 
 #[derive(Clone, Debug)]
-struct Constraint([Range<usize>; 4]);
+struct Constraint([IntervalSet<usize>; 4]);
 
 impl Default for Constraint {
     fn default() -> Self {
-        Self([1..4001, 1..4001, 1..4001, 1..4001])
+        Self(std::array::from_fn(|_| {
+            let mut values = IntervalSet::new();
+            values.insert(1..4001);
+            values
+        }))
     }
 }
 
 impl Deref for Constraint {
-    type Target = [Range<usize>; 4];
+    type Target = [IntervalSet<usize>; 4];
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -358,7 +346,7 @@ impl Day19 {
                     println!("Accepted {constraint:?}");
                     ans += constraint
                         .iter()
-                        .map(|range| range.end - range.start)
+                        .map(|values| values.len() as usize)
                         .product::<usize>();
                 }
             }
@@ -1,3 +1,4 @@
+use common::dot::{Dot, GraphDump};
 #[allow(unused_imports)]
 use helper::{print, println, Error, Lines, LinesOpt, Output, RunOutput, Runner};
 use std::collections::{BTreeMap, VecDeque};
@@ -17,6 +18,7 @@ enum Module {
 }
 
 pub struct Day20 {
+    names: Vec<String>,
     modules: Vec<Module>,
     connections: Vec<Vec<usize>>,
     broadcaster: usize,
@@ -27,6 +29,7 @@ pub struct Day20 {
 impl Day20 {
     pub fn new() -> Self {
         Self {
+            names: Vec::new(),
             modules: Vec::new(),
             connections: Vec::new(),
             broadcaster: usize::MAX,
@@ -82,6 +85,27 @@ impl Day20 {
     }
 }
 
+impl GraphDump for Day20 {
+    fn dot(&self) -> Dot {
+        let mut dot = Dot::digraph("modules");
+        for (name, module) in self.names.iter().zip(self.modules.iter()) {
+            let (shape, label) = match module {
+                Module::FlipFlop(_) => ("box", format!("%{name}")),
+                Module::Conjunction(_) => ("invtriangle", format!("&{name}")),
+                Module::Broadcaster => ("doublecircle", name.clone()),
+                Module::Output => ("doubleoctagon", name.clone()),
+            };
+            dot.node(name, &[("shape", shape), ("label", &label)]);
+        }
+        for (from, connections) in self.connections.iter().enumerate() {
+            for to in connections.iter() {
+                dot.edge(&self.names[from], &self.names[*to], &[]);
+            }
+        }
+        dot
+    }
+}
+
 impl Runner for Day20 {
     fn parse(&mut self, file: &[u8], _part: u8) -> Result<(), Error> {
         let mut names: Vec<String> = Vec::new();
@@ -144,6 +168,7 @@ impl Runner for Day20 {
         if let Some(idx) = names.iter().position(|n| n == "rx") {
             self.rx = idx;
         }
+        self.names = names;
 
         assert_ne!(self.broadcaster, usize::MAX);
         Ok(())
@@ -1,3 +1,4 @@
+use common::dot::{dump, NewDump};
 use helper::NewRunner;
 use std::collections::BTreeMap;
 
@@ -53,4 +54,8 @@ pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2023, 23), (2, || Box::new(day_23::Day23::new())));
     runners.insert((2023, 24), (2, || Box::new(day_24::Day24::new())));
     runners.insert((2023, 25), (1, || Box::new(day_25::Day25::new())));
-}
\ No newline at end of file
+}
+
+pub fn register_dumps(dumps: &mut BTreeMap<(usize, usize), NewDump>) {
+    dumps.insert((2023, 20), |input| dump(day_20::Day20::new(), input));
+}
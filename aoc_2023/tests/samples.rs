@@ -0,0 +1 @@
+common::sample_tests!(aoc_2023::register, 2023);
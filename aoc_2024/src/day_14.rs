@@ -1,3 +1,4 @@
+use common::frames::{self, Frame, BLACK, GREEN};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Point2D};
 use std::str::FromStr;
@@ -79,12 +80,27 @@ impl Day14 {
         quads.iter().product()
     }
 
+    fn frame(&self, t: isize) -> Frame {
+        let mut frame = Frame::new(self.tiles.x as usize, self.tiles.y as usize, BLACK);
+        for robot in self.robots.iter() {
+            let pos = robot.pos(t, self.tiles);
+            frame.set(pos.x as usize, pos.y as usize, GREEN);
+        }
+        frame
+    }
+
     fn part1(&mut self) -> Result<helper::RunOutput, Error> {
+        if frames::enabled() {
+            for t in 0..=100 {
+                frames::record(|| self.frame(t));
+            }
+        }
         Ok(self.safety_factor(100).into())
     }
 
     fn part2(&mut self) -> Result<helper::RunOutput, Error> {
         for t in 0..20_000 {
+            frames::record(|| self.frame(t));
             if self.safety_factor(t) < 100000000 {
                 return Ok(t.into());
             }
@@ -1,3 +1,4 @@
+use common::frames::{self, Frame, BLACK, GREY, RED, YELLOW};
 #[allow(unused_imports)]
 use helper::{print, println, Error, HashMap, HashSet, Lines, LinesOpt, Point2D};
 
@@ -69,8 +70,24 @@ impl Day15 {
         }
     }
 
+    fn frame(&self) -> Frame {
+        let width = self.tiles.keys().map(|p| p.x).max().unwrap_or(0) + 1;
+        let height = self.tiles.keys().map(|p| p.y).max().unwrap_or(0) + 1;
+        let mut frame = Frame::new(width, height, BLACK);
+        for (p, tile) in self.tiles.iter() {
+            let colour = match tile {
+                Tile::Wall => GREY,
+                Tile::Box | Tile::BoxLeft | Tile::BoxRight => YELLOW,
+            };
+            frame.set(p.x, p.y, colour);
+        }
+        frame.set(self.robot.x, self.robot.y, RED);
+        frame
+    }
+
     fn move_robot(&mut self) {
         'moves: for m in self.moves.iter() {
+            frames::record(|| self.frame());
             let delta = match m {
                 Move::Up => Point2D::new(0, usize::MAX),
                 Move::Down => Point2D::new(0, 1),
@@ -127,6 +144,7 @@ impl Day15 {
             }
             self.robot += delta;
         }
+        frames::record(|| self.frame());
     }
 
     fn gps_sum(&self) -> usize {
@@ -1,3 +1,4 @@
+use common::dot::{dump, NewDump};
 use helper::NewRunner;
 use std::collections::BTreeMap;
 
@@ -54,3 +55,7 @@ pub fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     runners.insert((2024, 24), (2, || Box::new(day_24::Day24::new())));
     runners.insert((2024, 25), (1, || Box::new(day_25::Day25::new())));
 }
+
+pub fn register_dumps(dumps: &mut BTreeMap<(usize, usize), NewDump>) {
+    dumps.insert((2024, 24), |input| dump(day_24::Day24::new(), input));
+}
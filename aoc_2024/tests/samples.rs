@@ -0,0 +1 @@
+common::sample_tests!(aoc_2024::register, 2024);
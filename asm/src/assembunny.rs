@@ -0,0 +1,247 @@
+use crate::{Args, Effect, InstructionSet, Machine, State, Value, Word};
+use helper::Error;
+use std::ops::{Deref, DerefMut};
+
+// Instruction set for 2016 days 12, 23 and 25.  Destinations are kept as values because `tgl`
+// can turn a jump into a copy to an immediate, which is then skipped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Assembunny {
+    Cpy(Value, Value),
+    Inc(Value),
+    Dec(Value),
+    Jnz(Value, Value),
+    Tgl(Value),
+    Out(Value),
+}
+
+impl Assembunny {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Inc(x) => Self::Dec(x),
+            Self::Dec(x) | Self::Tgl(x) | Self::Out(x) => Self::Inc(x),
+            Self::Cpy(x, y) => Self::Jnz(x, y),
+            Self::Jnz(x, y) => Self::Cpy(x, y),
+        }
+    }
+}
+
+impl InstructionSet for Assembunny {
+    const REGISTERS: &'static [&'static str] = &["a", "b", "c", "d"];
+
+    fn parse(op: &str, args: &Args) -> Result<Self, Error> {
+        match op {
+            "cpy" => Ok(Self::Cpy(args.value(0)?, Value::Reg(args.reg(1)?))),
+            "inc" => Ok(Self::Inc(Value::Reg(args.reg(0)?))),
+            "dec" => Ok(Self::Dec(Value::Reg(args.reg(0)?))),
+            "jnz" => Ok(Self::Jnz(args.value(0)?, args.value(1)?)),
+            "tgl" => Ok(Self::Tgl(args.value(0)?)),
+            "out" => Ok(Self::Out(args.value(0)?)),
+            _ => Err(args.invalid()),
+        }
+    }
+
+    fn execute(self, machine: &mut Machine<Self>) -> Effect {
+        let registers = &mut machine.registers;
+        match self {
+            Self::Cpy(x, Value::Reg(r)) => registers[r] = x.value(registers),
+            Self::Inc(Value::Reg(r)) => registers[r] += 1,
+            Self::Dec(Value::Reg(r)) => registers[r] -= 1,
+            Self::Jnz(x, y) => {
+                if x.value(registers) != 0 {
+                    return Effect::Jump(y.value(registers));
+                }
+            }
+            Self::Tgl(x) => {
+                let target = machine.pc + x.value(registers);
+                if target >= 0 {
+                    if let Some(inst) = machine.program.get_mut(target as usize) {
+                        *inst = inst.toggled();
+                    }
+                }
+            }
+            Self::Out(x) => return Effect::Output(x.value(registers)),
+            Self::Cpy(_, Value::Imm(_)) | Self::Inc(Value::Imm(_)) | Self::Dec(Value::Imm(_)) => {}
+        }
+        Effect::Next
+    }
+}
+
+// A run of instructions recognised as plain arithmetic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Block {
+    // inc target / dec counter / jnz counter -2, in either order
+    Add {
+        target: usize,
+        counter: usize,
+    },
+    // cpy source inner / <add loop of inner into target> / dec outer / jnz outer -5
+    Mul {
+        target: usize,
+        source: Value,
+        inner: usize,
+        outer: usize,
+    },
+}
+
+impl Block {
+    fn add(program: &[Assembunny], start: usize) -> Option<(usize, usize)> {
+        use Assembunny::*;
+        use Value::{Imm, Reg};
+        match program.get(start..start + 3)? {
+            [Inc(Reg(target)), Dec(Reg(counter)), Jnz(Reg(jump), Imm(-2))]
+            | [Dec(Reg(counter)), Inc(Reg(target)), Jnz(Reg(jump), Imm(-2))]
+                if counter == jump && target != counter =>
+            {
+                Some((*target, *counter))
+            }
+            _ => None,
+        }
+    }
+
+    fn find(program: &[Assembunny], start: usize) -> Option<Self> {
+        use Assembunny::*;
+        use Value::{Imm, Reg};
+        if let Some([Cpy(source, Reg(inner)), _, _, _, Dec(Reg(outer)), Jnz(Reg(jump), Imm(-5))]) =
+            program.get(start..start + 6)
+        {
+            if let Some((target, counter)) = Self::add(program, start + 1) {
+                let regs = [target, *inner, *outer];
+                if counter == *inner
+                    && jump == outer
+                    && target != *outer
+                    && inner != outer
+                    && !matches!(source, Reg(r) if regs.contains(r))
+                {
+                    return Some(Self::Mul {
+                        target,
+                        source: *source,
+                        inner: *inner,
+                        outer: *outer,
+                    });
+                }
+            }
+        }
+        Self::add(program, start).map(|(target, counter)| Self::Add { target, counter })
+    }
+
+    fn len(self) -> Word {
+        match self {
+            Self::Add { .. } => 3,
+            Self::Mul { .. } => 6,
+        }
+    }
+
+    // Applies the whole loop at once, or returns false if the loop would not terminate normally
+    // and so has to be stepped through.
+    fn execute(self, machine: &mut Machine<Assembunny>) -> bool {
+        let registers = &mut machine.registers;
+        match self {
+            Self::Add { target, counter } => {
+                let n = registers[counter];
+                if n <= 0 {
+                    return false;
+                }
+                registers[target] += n;
+                registers[counter] = 0;
+                machine.cycles += 3 * n as usize;
+            }
+            Self::Mul {
+                target,
+                source,
+                inner,
+                outer,
+            } => {
+                let (n, m) = (source.value(registers), registers[outer]);
+                if n <= 0 || m <= 0 {
+                    return false;
+                }
+                registers[target] += n * m;
+                registers[inner] = 0;
+                registers[outer] = 0;
+                machine.cycles += (3 * n as usize + 3) * m as usize;
+            }
+        }
+        machine.pc += self.len();
+        true
+    }
+}
+
+// Runs assembunny with add and multiply loops collapsed into single steps.  `tgl` can rewrite
+// any instruction, so every block which could include the toggled instruction is looked for
+// again after one executes.
+#[derive(Clone, Debug, Default)]
+pub struct Optimized {
+    machine: Machine<Assembunny>,
+    blocks: Vec<Option<Block>>,
+}
+
+impl Deref for Optimized {
+    type Target = Machine<Assembunny>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.machine
+    }
+}
+
+impl DerefMut for Optimized {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.machine
+    }
+}
+
+impl Optimized {
+    pub fn new(machine: Machine<Assembunny>) -> Self {
+        let blocks = (0..machine.program.len())
+            .map(|start| Block::find(&machine.program, start))
+            .collect();
+        Self { machine, blocks }
+    }
+
+    pub fn parse<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Result<Self, Error> {
+        Ok(Self::new(Machine::parse(lines)?))
+    }
+
+    pub fn step(&mut self) -> State {
+        let pc = self.machine.pc;
+        if pc >= 0 {
+            if let Some(Some(block)) = self.blocks.get(pc as usize) {
+                if block.execute(&mut self.machine) {
+                    return State::Running;
+                }
+            }
+        }
+
+        let toggled = match self.machine.current() {
+            Some(Assembunny::Tgl(x)) => Some(pc + x.value(&self.machine.registers)),
+            _ => None,
+        };
+        let state = self.machine.step();
+        if let Some(target) = toggled {
+            let program = &self.machine.program;
+            for start in (target - 5).max(0)..=target.min(program.len() as Word - 1) {
+                self.blocks[start as usize] = Block::find(program, start as usize);
+            }
+        }
+        state
+    }
+
+    // Runs until the program halts or needs input, queueing anything it outputs.
+    pub fn run(&mut self) -> State {
+        loop {
+            match self.step() {
+                State::Running => {}
+                State::Output(v) => self.machine.output.push_back(v),
+                state => return state,
+            }
+        }
+    }
+
+    pub fn run_until_output(&mut self) -> State {
+        loop {
+            match self.step() {
+                State::Running => {}
+                state => return state,
+            }
+        }
+    }
+}
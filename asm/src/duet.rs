@@ -0,0 +1,60 @@
+use crate::{Args, Effect, InstructionSet, Machine, Value, LOWERCASE};
+use helper::Error;
+
+// Instruction set for 2017 days 18 and 23.  `snd` outputs a value and `rcv` waits for input,
+// which matches the part 2 reading of day 18.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Duet {
+    Set(usize, Value),
+    Add(usize, Value),
+    Sub(usize, Value),
+    Mul(usize, Value),
+    Mod(usize, Value),
+    Jgz(Value, Value),
+    Jnz(Value, Value),
+    Snd(Value),
+    Rcv(usize),
+}
+
+impl InstructionSet for Duet {
+    const REGISTERS: &'static [&'static str] = LOWERCASE;
+
+    fn parse(op: &str, args: &Args) -> Result<Self, Error> {
+        match op {
+            "set" => Ok(Self::Set(args.reg(0)?, args.value(1)?)),
+            "add" => Ok(Self::Add(args.reg(0)?, args.value(1)?)),
+            "sub" => Ok(Self::Sub(args.reg(0)?, args.value(1)?)),
+            "mul" => Ok(Self::Mul(args.reg(0)?, args.value(1)?)),
+            "mod" => Ok(Self::Mod(args.reg(0)?, args.value(1)?)),
+            "jgz" => Ok(Self::Jgz(args.value(0)?, args.value(1)?)),
+            "jnz" => Ok(Self::Jnz(args.value(0)?, args.value(1)?)),
+            "snd" => Ok(Self::Snd(args.value(0)?)),
+            "rcv" => Ok(Self::Rcv(args.reg(0)?)),
+            _ => Err(args.invalid()),
+        }
+    }
+
+    fn execute(self, machine: &mut Machine<Self>) -> Effect {
+        let registers = &mut machine.registers;
+        match self {
+            Self::Set(x, y) => registers[x] = y.value(registers),
+            Self::Add(x, y) => registers[x] += y.value(registers),
+            Self::Sub(x, y) => registers[x] -= y.value(registers),
+            Self::Mul(x, y) => registers[x] *= y.value(registers),
+            Self::Mod(x, y) => registers[x] %= y.value(registers),
+            Self::Jgz(x, y) => {
+                if x.value(registers) > 0 {
+                    return Effect::Jump(y.value(registers));
+                }
+            }
+            Self::Jnz(x, y) => {
+                if x.value(registers) != 0 {
+                    return Effect::Jump(y.value(registers));
+                }
+            }
+            Self::Snd(x) => return Effect::Output(x.value(registers)),
+            Self::Rcv(x) => return Effect::Input(x),
+        }
+        Effect::Next
+    }
+}
@@ -0,0 +1,24 @@
+pub mod assembunny;
+pub mod duet;
+mod machine;
+mod parse;
+
+pub use machine::{Effect, InstructionSet, Machine, Registers, State};
+pub use parse::{parse, Args, LOWERCASE};
+
+pub type Word = isize;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Reg(usize),
+    Imm(Word),
+}
+
+impl Value {
+    pub fn value(&self, registers: &Registers) -> Word {
+        match self {
+            Self::Reg(r) => registers[*r],
+            Self::Imm(v) => *v,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Args, Word};
+use helper::Error;
+use std::{
+    collections::VecDeque,
+    ops::{Index, IndexMut},
+};
+
+pub trait InstructionSet: Copy + Sized {
+    const REGISTERS: &'static [&'static str];
+
+    fn parse(op: &str, args: &Args) -> Result<Self, Error>;
+    fn execute(self, machine: &mut Machine<Self>) -> Effect;
+}
+
+// What an executed instruction asks the machine to do next.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    Next,
+    Jump(Word),
+    Output(Word),
+    Input(usize),
+    Halt,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    Running,
+    Output(Word),
+    WaitingForInput,
+    Halted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Registers(Vec<Word>);
+
+impl Registers {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len])
+    }
+
+    pub fn clear(&mut self) {
+        self.0.iter_mut().for_each(|r| *r = 0);
+    }
+
+    pub fn as_slice(&self) -> &[Word] {
+        &self.0
+    }
+}
+
+impl Index<usize> for Registers {
+    type Output = Word;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl IndexMut<usize> for Registers {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Machine<I> {
+    pub program: Vec<I>,
+    pub registers: Registers,
+    pub pc: Word,
+    pub input: VecDeque<Word>,
+    pub output: VecDeque<Word>,
+    pub cycles: usize,
+    halted: bool,
+}
+
+impl<I: InstructionSet> Default for Machine<I> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<I: InstructionSet> Machine<I> {
+    pub fn new(program: Vec<I>) -> Self {
+        Self {
+            program,
+            registers: Registers::new(I::REGISTERS.len()),
+            pc: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            cycles: 0,
+            halted: false,
+        }
+    }
+
+    pub fn parse<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Result<Self, Error> {
+        Ok(Self::new(crate::parse(lines)?))
+    }
+
+    // Puts the machine back to its power-on state without touching the program.
+    pub fn reset(&mut self) {
+        self.registers.clear();
+        self.pc = 0;
+        self.input.clear();
+        self.output.clear();
+        self.cycles = 0;
+        self.halted = false;
+    }
+
+    pub fn current(&self) -> Option<&I> {
+        if self.pc < 0 {
+            None
+        } else {
+            self.program.get(self.pc as usize)
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted || self.current().is_none()
+    }
+
+    pub fn step(&mut self) -> State {
+        if self.halted {
+            return State::Halted;
+        }
+        let Some(inst) = self.current().copied() else {
+            self.halted = true;
+            return State::Halted;
+        };
+
+        let state = match inst.execute(self) {
+            Effect::Next => {
+                self.pc += 1;
+                State::Running
+            }
+            Effect::Jump(offset) => {
+                self.pc += offset;
+                State::Running
+            }
+            Effect::Output(v) => {
+                self.pc += 1;
+                State::Output(v)
+            }
+            Effect::Input(r) => match self.input.pop_front() {
+                Some(v) => {
+                    self.registers[r] = v;
+                    self.pc += 1;
+                    State::Running
+                }
+                None => return State::WaitingForInput,
+            },
+            Effect::Halt => {
+                self.halted = true;
+                State::Halted
+            }
+        };
+
+        self.cycles += 1;
+        state
+    }
+
+    // Runs until the program halts or needs input, queueing anything it outputs.
+    pub fn run(&mut self) -> State {
+        loop {
+            match self.step() {
+                State::Running => {}
+                State::Output(v) => self.output.push_back(v),
+                state => return state,
+            }
+        }
+    }
+
+    pub fn run_until_output(&mut self) -> State {
+        loop {
+            match self.step() {
+                State::Running => {}
+                state => return state,
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{InstructionSet, Value, Word};
+use helper::Error;
+
+pub const LOWERCASE: &[&str] = &[
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s",
+    "t", "u", "v", "w", "x", "y", "z",
+];
+
+// Operands of a single line, split on spaces and commas so both "cpy a b" and "jie a, +4"
+// parse the same way.
+pub struct Args<'a> {
+    line: &'a str,
+    registers: &'static [&'static str],
+    args: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn new(line: &'a str, registers: &'static [&'static str]) -> (&'a str, Self) {
+        let mut parts = line.split([' ', ',']).filter(|s| !s.is_empty());
+        let op = parts.next().unwrap_or("");
+        (
+            op,
+            Self {
+                line,
+                registers,
+                args: parts.collect(),
+            },
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    pub fn invalid(&self) -> Error {
+        Error::InvalidInput(self.line.into())
+    }
+
+    fn arg(&self, i: usize) -> Result<&'a str, Error> {
+        self.args.get(i).copied().ok_or_else(|| self.invalid())
+    }
+
+    pub fn reg(&self, i: usize) -> Result<usize, Error> {
+        let arg = self.arg(i)?;
+        self.registers
+            .iter()
+            .position(|r| *r == arg)
+            .ok_or_else(|| Error::InvalidInput(format!("Not a Reg: {arg:?} in {:?}", self.line)))
+    }
+
+    pub fn imm(&self, i: usize) -> Result<Word, Error> {
+        let arg = self.arg(i)?;
+        arg.parse()
+            .map_err(|_| Error::InvalidInput(format!("Not an Imm: {arg:?} in {:?}", self.line)))
+    }
+
+    pub fn value(&self, i: usize) -> Result<Value, Error> {
+        if let Ok(v) = self.imm(i) {
+            Ok(Value::Imm(v))
+        } else {
+            Ok(Value::Reg(self.reg(i)?))
+        }
+    }
+}
+
+pub fn parse<I, S>(lines: impl IntoIterator<Item = S>) -> Result<Vec<I>, Error>
+where
+    I: InstructionSet,
+    S: AsRef<str>,
+{
+    let mut program = Vec::new();
+    for line in lines {
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (op, args) = Args::new(line, I::REGISTERS);
+        program.push(I::parse(op, &args)?);
+    }
+    Ok(program)
+}
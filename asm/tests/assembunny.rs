@@ -0,0 +1,44 @@
+use asm::{
+    assembunny::{Assembunny, Optimized},
+    Machine, State,
+};
+
+// Three passes of b += a * 3, with a counting down, and a `tgl` after each pass rewriting the
+// instruction at `target` inside the multiply loop
+fn program(target: isize) -> Vec<String> {
+    [
+        "cpy 3 a",
+        "cpy a d",
+        "cpy 3 c",
+        "inc b",
+        "dec c",
+        "jnz c -2",
+        "dec d",
+        "jnz d -5",
+        &format!("cpy {} c", target - 9),
+        "tgl c",
+        "dec a",
+        "jnz a -10",
+    ]
+    .map(String::from)
+    .into()
+}
+
+#[test]
+fn toggling_inside_a_multiply_loop() {
+    // The increment, the inner jump and the outer jump
+    for target in [3, 5, 7] {
+        let mut plain = Machine::<Assembunny>::parse(program(target)).unwrap();
+        let mut optimized = Optimized::parse(program(target)).unwrap();
+        assert_eq!(plain.run(), State::Halted);
+        assert_eq!(optimized.run(), State::Halted);
+        assert_eq!(optimized.registers, plain.registers, "toggling {target}");
+        assert_eq!(optimized.program, plain.program, "toggling {target}");
+        assert_eq!(optimized.cycles, plain.cycles, "toggling {target}");
+    }
+
+    // Untoggled b gains 9, then loses 6 and then gains 3 again
+    let mut optimized = Optimized::parse(program(3)).unwrap();
+    optimized.run();
+    assert_eq!(optimized.registers[1], 6);
+}
@@ -0,0 +1,60 @@
+use helper::Error;
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+// What `check` fails with once the run has been cancelled
+pub const CANCELLED: &str = "Cancelled";
+
+// Shared between the runner, which cancels a day once it runs out of time, and the day, which
+// polls it in loops that might never end.  Polling costs a relaxed load.
+#[derive(Clone, Debug, Default)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            Err(Error::Runner(CANCELLED.into()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Token> = RefCell::new(Token::new());
+}
+
+// Makes `token` the one polled by `check` on this thread.  The runner does this on the thread
+// running a day.
+pub fn install(token: Token) {
+    CURRENT.with(|current| *current.borrow_mut() = token);
+}
+
+// This thread's token, for handing on to any threads a day starts
+pub fn current() -> Token {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+// Fails once this thread's run has been cancelled, so hot loops can bail out with
+//
+//     common::cancel::check()?;
+pub fn check() -> Result<(), Error> {
+    CURRENT.with(|current| current.borrow().check())
+}
@@ -0,0 +1,343 @@
+use crate::dot::Dot;
+use helper::{Error, HashMap};
+use std::collections::VecDeque;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Assign,
+    Not,
+    And,
+    Or,
+    Xor,
+    LShift,
+    RShift,
+}
+
+impl Op {
+    fn arity(self) -> usize {
+        match self {
+            Self::Assign | Self::Not => 1,
+            _ => 2,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Assign => "=",
+            Self::Not => "NOT",
+            Self::And => "AND",
+            Self::Or => "OR",
+            Self::Xor => "XOR",
+            Self::LShift => "LSHIFT",
+            Self::RShift => "RSHIFT",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Input {
+    Const(u64),
+    Wire(usize),
+}
+
+impl Input {
+    pub fn wire(self) -> Option<usize> {
+        match self {
+            Self::Wire(wire) => Some(wire),
+            Self::Const(_) => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Gate {
+    pub op: Op,
+    pub output: usize,
+    inputs: [Input; 2],
+}
+
+impl Gate {
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs[..self.op.arity()]
+    }
+}
+
+// A netlist of gates driving named wires, all of which carry values `width` bits wide.  Lines
+// look like `x AND y -> z`, `NOT x -> y` or `123 -> x`.
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    mask: u64,
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    gates: Vec<Gate>,
+    overrides: HashMap<usize, u64>,
+}
+
+impl Circuit {
+    pub fn new(width: u32) -> Result<Self, Error> {
+        if !(1..=64).contains(&width) {
+            return Err(Error::InvalidInput(format!(
+                "Wires can't be {width} bits wide"
+            )));
+        }
+        Ok(Self {
+            mask: u64::MAX >> (64 - width),
+            names: Vec::new(),
+            ids: HashMap::default(),
+            gates: Vec::new(),
+            overrides: HashMap::default(),
+        })
+    }
+
+    // The id of a wire, adding it if it is new
+    pub fn wire(&mut self, name: &str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            *id
+        } else {
+            let id = self.names.len();
+            self.ids.insert(name.into(), id);
+            self.names.push(name.into());
+            id
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, wire: usize) -> &str {
+        &self.names[wire]
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    pub fn add_gate(&mut self, op: Op, inputs: &[Input], output: usize) -> Result<(), Error> {
+        if inputs.len() != op.arity() {
+            return Err(Error::InvalidInput(format!(
+                "{} takes {} inputs",
+                op.name(),
+                op.arity()
+            )));
+        }
+        let mut gate = Gate {
+            op,
+            output,
+            inputs: [Input::Const(0); 2],
+        };
+        gate.inputs[..inputs.len()].copy_from_slice(inputs);
+        self.gates.push(gate);
+        Ok(())
+    }
+
+    pub fn parse_gate(&mut self, line: &str) -> Result<(), Error> {
+        let bad = || Error::InvalidInput(format!("Bad gate {line:?}"));
+        let (expr, output) = line.split_once(" -> ").ok_or_else(bad)?;
+        let output = self.parse_input(output)?.wire().ok_or_else(bad)?;
+        let parts: Vec<&str> = expr.split_whitespace().collect();
+        let (op, inputs) = match parts[..] {
+            [a] => (Op::Assign, vec![a]),
+            ["NOT", a] => (Op::Not, vec![a]),
+            [a, op, b] => {
+                let op = match op {
+                    "AND" => Op::And,
+                    "OR" => Op::Or,
+                    "XOR" => Op::Xor,
+                    "LSHIFT" => Op::LShift,
+                    "RSHIFT" => Op::RShift,
+                    _ => return Err(bad()),
+                };
+                (op, vec![a, b])
+            }
+            _ => return Err(bad()),
+        };
+        let inputs = inputs
+            .into_iter()
+            .map(|input| self.parse_input(input))
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.add_gate(op, &inputs, output)
+    }
+
+    fn parse_input(&mut self, s: &str) -> Result<Input, Error> {
+        if s.starts_with(|c: char| c.is_ascii_digit()) {
+            Ok(Input::Const(s.parse()?))
+        } else if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric()) {
+            Ok(Input::Wire(self.wire(s)))
+        } else {
+            Err(Error::InvalidInput(format!("Invalid wire name: {s:?}")))
+        }
+    }
+
+    // Forces a wire to a value, ignoring whatever drives it
+    pub fn set_override(&mut self, wire: usize, value: Option<u64>) {
+        match value {
+            Some(value) => self.overrides.insert(wire, value & self.mask),
+            None => self.overrides.remove(&wire),
+        };
+    }
+
+    // Swaps the gates driving two wires
+    pub fn swap_outputs(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        let driver = |wire| {
+            self.gates
+                .iter()
+                .position(|gate| gate.output == wire)
+                .ok_or_else(|| Error::Runner(format!("Wire {} is not driven", self.names[wire])))
+        };
+        let (a, b) = (driver(a)?, driver(b)?);
+        let output = self.gates[a].output;
+        self.gates[a].output = self.gates[b].output;
+        self.gates[b].output = output;
+        Ok(())
+    }
+
+    // The value of every wire, indexed by id.  Gates are evaluated in topological order, so a
+    // loop or a wire with nothing driving it is an error.
+    pub fn eval(&self) -> Result<Vec<u64>, Error> {
+        let mut values: Vec<Option<u64>> = vec![None; self.names.len()];
+        for (wire, value) in self.overrides.iter() {
+            values[*wire] = Some(*value);
+        }
+        let mut driver = vec![None; self.names.len()];
+        for (i, gate) in self.gates.iter().enumerate() {
+            if values[gate.output].is_none() && driver[gate.output].replace(i).is_some() {
+                return Err(Error::InvalidInput(format!(
+                    "Wire {} has more than one driver",
+                    self.names[gate.output]
+                )));
+            }
+        }
+
+        // Count the inputs each gate is still waiting on
+        let mut pending = vec![0; self.gates.len()];
+        let mut waiting: Vec<Vec<usize>> = vec![Vec::new(); self.names.len()];
+        let mut ready = VecDeque::new();
+        for (i, gate) in self.gates.iter().enumerate() {
+            if driver[gate.output] != Some(i) {
+                continue;
+            }
+            for wire in gate.inputs().iter().filter_map(|input| input.wire()) {
+                if values[wire].is_none() {
+                    pending[i] += 1;
+                    waiting[wire].push(i);
+                }
+            }
+            if pending[i] == 0 {
+                ready.push_back(i);
+            }
+        }
+
+        while let Some(i) = ready.pop_front() {
+            let gate = &self.gates[i];
+            values[gate.output] = Some(self.apply(gate, &values));
+            for j in waiting[gate.output].iter().copied() {
+                pending[j] -= 1;
+                if pending[j] == 0 {
+                    ready.push_back(j);
+                }
+            }
+        }
+
+        if let Some(wire) = values.iter().position(|v| v.is_none()) {
+            return Err(self.stuck(wire, &values, &driver));
+        }
+        Ok(values.into_iter().flatten().collect())
+    }
+
+    fn apply(&self, gate: &Gate, values: &[Option<u64>]) -> u64 {
+        let value = |input: Input| match input {
+            Input::Const(v) => v,
+            Input::Wire(wire) => values[wire].expect("gate evaluated before its inputs"),
+        };
+        let a = value(gate.inputs[0]);
+        let b = value(gate.inputs[1]);
+        let v = match gate.op {
+            Op::Assign => a,
+            Op::Not => !a,
+            Op::And => a & b,
+            Op::Or => a | b,
+            Op::Xor => a ^ b,
+            Op::LShift => a.checked_shl(b as u32).unwrap_or(0),
+            Op::RShift => a.checked_shr(b as u32).unwrap_or(0),
+        };
+        v & self.mask
+    }
+
+    // Explains why `wire` never got a value by following unresolved inputs back until either an
+    // undriven wire or a loop turns up.
+    fn stuck(&self, mut wire: usize, values: &[Option<u64>], driver: &[Option<usize>]) -> Error {
+        let mut path: Vec<usize> = Vec::new();
+        loop {
+            if let Some(start) = path.iter().position(|w| *w == wire) {
+                let names: Vec<&str> = path[start..]
+                    .iter()
+                    .rev()
+                    .map(|w| self.names[*w].as_str())
+                    .collect();
+                return Error::Runner(format!("Circuit has a loop: {}", names.join(" -> ")));
+            }
+            path.push(wire);
+            let Some(gate) = driver[wire] else {
+                return Error::Runner(format!("Wire {} is not driven", self.names[wire]));
+            };
+            wire = self.gates[gate]
+                .inputs()
+                .iter()
+                .filter_map(|input| input.wire())
+                .find(|w| values[*w].is_none())
+                .expect("stuck gate has an unresolved input");
+        }
+    }
+
+    // Reads the wires named `prefix` followed by a bit number, such as z00, z01, ..., as one
+    // binary number.  Other wires starting with `prefix` are left out.
+    pub fn bus(&self, values: &[u64], prefix: char) -> Result<u64, Error> {
+        let mut bus = 0;
+        for (wire, name) in self.names.iter().enumerate() {
+            let Some(bit) = name.strip_prefix(prefix) else {
+                continue;
+            };
+            if bit.is_empty() || !bit.bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            let bit = bit
+                .parse::<u32>()
+                .ok()
+                .filter(|bit| *bit < u64::BITS)
+                .ok_or_else(|| Error::InvalidInput(format!("Wire {name} is past bit 63")))?;
+            bus |= (values[wire] & 1) << bit;
+        }
+        Ok(bus)
+    }
+
+    // The netlist as a Graphviz digraph, with a box for each gate and an ellipse for each wire
+    pub fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph("circuit");
+        dot.attr("rankdir=LR");
+        for (wire, name) in self.names.iter().enumerate() {
+            if self.overrides.contains_key(&wire) {
+                dot.node(name, &[("style", "bold")]);
+            } else {
+                dot.node(name, &[]);
+            }
+        }
+        for (i, gate) in self.gates.iter().enumerate() {
+            // Wire names are alphanumeric, so these can't clash with them
+            let id = format!("gate {i}");
+            dot.node(&id, &[("shape", "box"), ("label", gate.op.name())]);
+            for (j, input) in gate.inputs().iter().enumerate() {
+                match input {
+                    Input::Wire(wire) => dot.edge(&self.names[*wire], &id, &[]),
+                    Input::Const(v) => {
+                        let c = format!("const {i} {j}");
+                        dot.node(&c, &[("shape", "plaintext"), ("label", &v.to_string())]);
+                        dot.edge(&c, &id, &[]);
+                    }
+                }
+            }
+            dot.edge(&id, &self.names[gate.output], &[]);
+        }
+        dot
+    }
+}
@@ -0,0 +1,285 @@
+use helper::HashMap;
+
+// A box of whole cells in N dimensions, from its lowest to its highest corner inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid<const N: usize> {
+    pub min: [isize; N],
+    pub max: [isize; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    // None if the box would be empty along any axis
+    pub fn new(min: [isize; N], max: [isize; N]) -> Option<Self> {
+        (0..N)
+            .all(|a| min[a] <= max[a])
+            .then_some(Self { min, max })
+    }
+
+    pub fn contains(&self, p: [isize; N]) -> bool {
+        (0..N).all(|a| self.min[a] <= p[a] && p[a] <= self.max[a])
+    }
+
+    pub fn volume(&self) -> u128 {
+        (0..N)
+            .map(|a| (self.max[a] - self.min[a]) as u128 + 1)
+            .product()
+    }
+
+    // Faces of the cells inside which face a cell outside
+    pub fn surface_area(&self) -> u128 {
+        (0..N)
+            .map(|a| 2 * self.volume() / ((self.max[a] - self.min[a]) as u128 + 1))
+            .sum()
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        Self::new(
+            std::array::from_fn(|a| self.min[a].max(other.min[a])),
+            std::array::from_fn(|a| self.max[a].min(other.max[a])),
+        )
+    }
+
+    // The cells not in `other`, as at most two boxes per axis which don't overlap.  Each axis in
+    // turn has the slabs either side of `other` cut off, leaving the rest to the next axis.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if self.intersect(other).is_none() {
+            return vec![*self];
+        }
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for a in 0..N {
+            if rest.min[a] < other.min[a] {
+                let mut piece = rest;
+                piece.max[a] = other.min[a] - 1;
+                pieces.push(piece);
+                rest.min[a] = other.min[a];
+            }
+            if rest.max[a] > other.max[a] {
+                let mut piece = rest;
+                piece.min[a] = other.max[a] + 1;
+                pieces.push(piece);
+                rest.max[a] = other.max[a];
+            }
+        }
+        pieces
+    }
+
+    fn shifted(&self, axis: usize, by: isize) -> Self {
+        let mut shifted = *self;
+        shifted.min[axis] += by;
+        shifted.max[axis] += by;
+        shifted
+    }
+}
+
+// How a set keeps track of the cells in it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    // Boxes which never overlap, splitting those already in the set whenever a box is added or
+    // taken away across them
+    #[default]
+    Fragments,
+    // Boxes which can overlap, each counted some number of times, with the overlaps counted
+    // against them to even out.  Adding or taking away a box never splits one, but the number
+    // of boxes grows with how much they overlap.
+    InclusionExclusion,
+}
+
+// A set of cells in N dimensions kept as boxes, each counted some number of times so that
+// every cell in the set is counted once in total and every cell outside it not at all
+#[derive(Clone, Debug, Default)]
+pub struct CuboidSet<const N: usize> {
+    strategy: Strategy,
+    boxes: Vec<(Cuboid<N>, i64)>,
+}
+
+impl<const N: usize> CuboidSet<N> {
+    pub fn new(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            boxes: Vec::new(),
+        }
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.volume() == 0
+    }
+
+    pub fn contains(&self, p: [isize; N]) -> bool {
+        self.boxes
+            .iter()
+            .filter(|(b, _)| b.contains(p))
+            .map(|&(_, count)| count)
+            .sum::<i64>()
+            > 0
+    }
+
+    pub fn volume(&self) -> u128 {
+        self.boxes
+            .iter()
+            .map(|&(b, count)| b.volume() as i128 * count as i128)
+            .sum::<i128>() as u128
+    }
+
+    // Faces of the cells in the set which face a cell outside it.  Along each axis that is
+    // every face of a cell in the set less the two faces of each pair of neighbours both in the
+    // set, and the pairs are counted box by box against every box moved one cell back.
+    pub fn surface_area(&self) -> u128 {
+        let volume = self.volume() as i128;
+        let mut area = 0;
+        for a in 0..N {
+            let mut pairs = 0;
+            for &(b, count) in self.boxes.iter() {
+                for &(other, other_count) in self.boxes.iter() {
+                    if let Some(i) = b.intersect(&other.shifted(a, -1)) {
+                        pairs += i.volume() as i128 * count as i128 * other_count as i128;
+                    }
+                }
+            }
+            area += 2 * volume - 2 * pairs;
+        }
+        area as u128
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid<N>) {
+        match self.strategy {
+            Strategy::Fragments => {
+                self.remove(cuboid);
+                self.boxes.push((cuboid, 1));
+            }
+            Strategy::InclusionExclusion => {
+                let mut counts = self.overlaps(&cuboid, -1);
+                counts.push((cuboid, 1));
+                self.add_counts(counts);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, cuboid: Cuboid<N>) {
+        match self.strategy {
+            Strategy::Fragments => {
+                let mut pieces = Vec::new();
+                self.boxes.retain(|(b, _)| {
+                    if b.intersect(&cuboid).is_none() {
+                        return true;
+                    }
+                    pieces.extend(b.subtract(&cuboid).into_iter().map(|p| (p, 1)));
+                    false
+                });
+                self.boxes.extend(pieces);
+            }
+            Strategy::InclusionExclusion => {
+                let counts = self.overlaps(&cuboid, -1);
+                self.add_counts(counts);
+            }
+        }
+    }
+
+    // The cells of the set inside `cuboid`
+    pub fn clip(&self, cuboid: &Cuboid<N>) -> Self {
+        Self {
+            strategy: self.strategy,
+            boxes: self.overlaps(cuboid, 1),
+        }
+    }
+
+    // The sets below all keep the strategy of `self` if `other` uses the same one, and count
+    // overlaps otherwise, as fragments can't be made from counted boxes without splitting them
+    // all up.
+    pub fn union(&self, other: &Self) -> Self {
+        if self.both_fragments(other) {
+            let mut set = self.clone();
+            for &(b, _) in other.boxes.iter() {
+                set.insert(b);
+            }
+            return set;
+        }
+        let mut set = self.counted();
+        let mut counts = other.boxes.clone();
+        counts.extend(self.overlapping(other, -1));
+        set.add_counts(counts);
+        set
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut set = match self.both_fragments(other) {
+            true => Self::new(Strategy::Fragments),
+            false => Self::new(Strategy::InclusionExclusion),
+        };
+        // Boxes which don't overlap within each set don't overlap once clipped either
+        set.boxes = self.overlapping(other, 1);
+        if set.strategy == Strategy::InclusionExclusion {
+            set.merge_counts();
+        }
+        set
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        if self.both_fragments(other) {
+            let mut set = self.clone();
+            for &(b, _) in other.boxes.iter() {
+                set.remove(b);
+            }
+            return set;
+        }
+        let mut set = self.counted();
+        let counts = self.overlapping(other, -1);
+        set.add_counts(counts);
+        set
+    }
+
+    fn both_fragments(&self, other: &Self) -> bool {
+        self.strategy == Strategy::Fragments && other.strategy == Strategy::Fragments
+    }
+
+    fn counted(&self) -> Self {
+        Self {
+            strategy: Strategy::InclusionExclusion,
+            boxes: self.boxes.clone(),
+        }
+    }
+
+    // Each box of the set clipped to `cuboid`, counted `sign` times as often
+    fn overlaps(&self, cuboid: &Cuboid<N>, sign: i64) -> Vec<(Cuboid<N>, i64)> {
+        self.boxes
+            .iter()
+            .filter_map(|(b, count)| Some((b.intersect(cuboid)?, count * sign)))
+            .collect()
+    }
+
+    // Every box of the set clipped to every box of `other`
+    fn overlapping(&self, other: &Self, sign: i64) -> Vec<(Cuboid<N>, i64)> {
+        other
+            .boxes
+            .iter()
+            .flat_map(|(b, count)| self.overlaps(b, sign * count))
+            .collect()
+    }
+
+    fn add_counts(&mut self, counts: Vec<(Cuboid<N>, i64)>) {
+        self.boxes.extend(counts);
+        self.merge_counts();
+    }
+
+    // Adds up the counts of boxes which are the same, dropping those which even out to nothing
+    fn merge_counts(&mut self) {
+        let mut counts: HashMap<Cuboid<N>, i64> = HashMap::default();
+        let mut order = Vec::new();
+        for (b, count) in self.boxes.drain(..) {
+            let total = counts.entry(b).or_insert_with(|| {
+                order.push(b);
+                0
+            });
+            *total += count;
+        }
+        self.boxes = order
+            .into_iter()
+            .map(|b| (b, counts[&b]))
+            .filter(|&(_, count)| count != 0)
+            .collect();
+    }
+}
@@ -0,0 +1,112 @@
+use helper::Error;
+use std::str::FromStr;
+
+// A position in a line of text being parsed by hand, for the puzzles whose input is some nested
+// bracket syntax.  Errors carry the byte offset they happened at.
+#[derive(Copy, Clone, Debug)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    pub fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    // Consumes `b` if it is next
+    pub fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Consumes `s` if it is next
+    pub fn eat_str(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    // Moves past the next `n` bytes, which must end on a char boundary
+    pub fn advance(&mut self, n: usize) {
+        assert!(self.input.is_char_boundary(self.pos + n));
+        self.pos += n;
+    }
+
+    pub fn expect(&mut self, b: u8) -> Result<(), Error> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected {:?}", b as char)))
+        }
+    }
+
+    // Consumes the ASCII bytes matching `f`, so the result always falls on char boundaries
+    pub fn take_while<F>(&mut self, f: F) -> &'a str
+    where
+        F: Fn(u8) -> bool,
+    {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii() && f(b)) {
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.take_while(|b| b.is_ascii_whitespace());
+    }
+
+    pub fn unsigned<T: FromStr>(&mut self) -> Result<T, Error> {
+        let start = self.pos;
+        let digits = self.take_while(|b| b.is_ascii_digit());
+        digits
+            .parse()
+            .map_err(|_| Error::InvalidInput(format!("Expected a number at {start}")))
+    }
+
+    // Checks that the whole input has been consumed
+    pub fn finish(&self) -> Result<(), Error> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("Unexpected trailing input"))
+        }
+    }
+
+    pub fn error(&self, msg: impl std::fmt::Display) -> Error {
+        match self.rest().chars().next() {
+            Some(c) => Error::InvalidInput(format!("{msg} at {}, found {c:?}", self.pos)),
+            None => Error::InvalidInput(format!("{msg} at {}, found end of input", self.pos)),
+        }
+    }
+}
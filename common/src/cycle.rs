@@ -0,0 +1,203 @@
+use crate::cancel;
+use helper::{Error, HashMap};
+use std::{
+    collections::hash_map::Entry,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+// Where a sequence of states starts repeating.  Step 0 is the starting state, and from step
+// `start` on the states go round a loop `len` steps long.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    // The first step whose state matches step `n`, which is before `start + len`
+    pub fn fast_forward(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+
+    // A value derived from the states which grows by the same amount every time round the loop,
+    // like the height of a tower, at step `n`.  `values` holds it for at least every step up to
+    // and including `start + len`.
+    pub fn extrapolate<T>(&self, n: usize, values: &[T]) -> Result<T, Error>
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+    {
+        let end = self.start + self.len;
+        if values.len() <= end {
+            return Err(Error::Runner(format!(
+                "{} values cannot be extrapolated from a cycle ending at {end}",
+                values.len()
+            )));
+        }
+        let first = self.fast_forward(n);
+        if n < self.start {
+            return Ok(values[first]);
+        }
+        let laps = T::try_from((n - self.start) / self.len)
+            .map_err(|_| Error::Runner(format!("Cannot extrapolate to step {n}")))?;
+        Ok(values[first] + laps * (values[end] - values[self.start]))
+    }
+}
+
+// Hash based detection for simulations which step their state in place.  Each state is given
+// to `push` in turn, starting with the one at step 0:
+//
+//     let mut detector = Detector::new();
+//     while detector.push(key(&state)).is_none() {
+//         step(&mut state);
+//     }
+pub struct Detector<K> {
+    seen: HashMap<K, usize>,
+    steps: usize,
+}
+
+impl<K> Default for Detector<K> {
+    fn default() -> Self {
+        Self {
+            seen: HashMap::default(),
+            steps: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq> Detector<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Records the state at the next step, returning the cycle if it has been seen before
+    pub fn push(&mut self, key: K) -> Option<Cycle> {
+        let step = self.steps;
+        self.steps += 1;
+        match self.seen.entry(key) {
+            Entry::Occupied(e) => Some(Cycle {
+                start: *e.get(),
+                len: step - e.get(),
+            }),
+            Entry::Vacant(e) => {
+                e.insert(step);
+                None
+            }
+        }
+    }
+}
+
+// Every state up to where the cycle closes, so any step can be looked up
+pub struct History<S> {
+    pub cycle: Cycle,
+    // The state at each step before `cycle.start + cycle.len`
+    pub states: Vec<S>,
+}
+
+impl<S> History<S> {
+    pub fn nth(&self, n: usize) -> &S {
+        &self.states[self.cycle.fast_forward(n)]
+    }
+}
+
+// Steps from `start` until a state repeats, remembering them all
+pub fn find<S, F>(start: S, mut step: F) -> Result<History<S>, Error>
+where
+    S: Clone + Hash + Eq,
+    F: FnMut(&mut S),
+{
+    let mut detector = Detector::new();
+    let mut states = Vec::new();
+    let mut state = start;
+    loop {
+        cancel::check()?;
+        if let Some(cycle) = detector.push(state.clone()) {
+            return Ok(History { cycle, states });
+        }
+        states.push(state.clone());
+        step(&mut state);
+    }
+}
+
+// Brent's algorithm, which keeps two states rather than every one seen and takes fewer steps
+// than Floyd's
+pub fn brent<S, F>(start: S, mut step: F) -> Result<Cycle, Error>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&mut S),
+{
+    // Find the length by moving the tortoise up to the hare at every power of two
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    step(&mut hare);
+    while tortoise != hare {
+        cancel::check()?;
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        step(&mut hare);
+        len += 1;
+    }
+
+    // Then with the hare `len` steps ahead the two meet where the loop starts
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..len {
+        step(&mut hare);
+    }
+    let mut first = 0;
+    while tortoise != hare {
+        cancel::check()?;
+        step(&mut tortoise);
+        step(&mut hare);
+        first += 1;
+    }
+    Ok(Cycle { start: first, len })
+}
+
+// Floyd's tortoise and hare, with the hare moving two steps for each of the tortoise's
+pub fn floyd<S, F>(start: S, mut step: F) -> Result<Cycle, Error>
+where
+    S: Clone + PartialEq,
+    F: FnMut(&mut S),
+{
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    loop {
+        cancel::check()?;
+        step(&mut tortoise);
+        step(&mut hare);
+        step(&mut hare);
+        if tortoise == hare {
+            break;
+        }
+    }
+
+    // The hare is now a whole number of loops ahead, so restarting the tortoise has them meet
+    // where the loop starts
+    let mut tortoise = start;
+    let mut first = 0;
+    while tortoise != hare {
+        cancel::check()?;
+        step(&mut tortoise);
+        step(&mut hare);
+        first += 1;
+    }
+
+    let mut len = 1;
+    step(&mut hare);
+    while tortoise != hare {
+        cancel::check()?;
+        step(&mut hare);
+        len += 1;
+    }
+    Ok(Cycle { start: first, len })
+}
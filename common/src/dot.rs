@@ -0,0 +1,78 @@
+use helper::{Error, Runner};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+};
+
+pub type NewDump = fn(&[u8]) -> Result<String, Error>;
+pub type RegisterDumps = fn(&mut BTreeMap<(usize, usize), NewDump>);
+
+// Days whose parsed input is a graph implement this so `runner --dot` can draw it.  They are
+// registered separately from the runners as a function parsing the input and returning the
+// graph, which `dump` provides:
+//
+//     dumps.insert((2023, 20), |input| common::dot::dump(day_20::Day20::new(), input));
+pub trait GraphDump: Runner {
+    fn dot(&self) -> Dot;
+}
+
+pub fn dump<T: GraphDump>(mut day: T, input: &[u8]) -> Result<String, Error> {
+    day.parse(input, 1)?;
+    Ok(day.dot().finish())
+}
+
+// A Graphviz graph being written out.  Ids and attribute values are quoted, so any name will do.
+pub struct Dot {
+    out: String,
+    edge: &'static str,
+}
+
+impl Dot {
+    pub fn digraph(name: &str) -> Self {
+        Self {
+            out: format!("digraph {name:?} {{\n"),
+            edge: "->",
+        }
+    }
+
+    pub fn graph(name: &str) -> Self {
+        Self {
+            out: format!("graph {name:?} {{\n"),
+            edge: "--",
+        }
+    }
+
+    // A graph wide setting, such as `rankdir=LR`
+    pub fn attr(&mut self, attr: &str) {
+        let _ = writeln!(self.out, "    {attr};");
+    }
+
+    pub fn node(&mut self, id: impl Display, attrs: &[(&str, &str)]) {
+        let _ = write!(self.out, "    {:?}", id.to_string());
+        self.attrs(attrs);
+    }
+
+    pub fn edge(&mut self, from: impl Display, to: impl Display, attrs: &[(&str, &str)]) {
+        let _ = write!(
+            self.out,
+            "    {:?} {} {:?}",
+            from.to_string(),
+            self.edge,
+            to.to_string()
+        );
+        self.attrs(attrs);
+    }
+
+    fn attrs(&mut self, attrs: &[(&str, &str)]) {
+        if !attrs.is_empty() {
+            let attrs: Vec<String> = attrs.iter().map(|(k, v)| format!("{k}={v:?}")).collect();
+            let _ = write!(self.out, " [{}]", attrs.join(", "));
+        }
+        self.out.push_str(";\n");
+    }
+
+    pub fn finish(mut self) -> String {
+        self.out.push_str("}\n");
+        self.out
+    }
+}
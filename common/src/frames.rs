@@ -0,0 +1,157 @@
+use crate::grid::Grid;
+use helper::Error;
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const GREY: Rgb = [96, 96, 96];
+pub const RED: Rgb = [220, 40, 40];
+pub const GREEN: Rgb = [40, 200, 60];
+pub const BLUE: Rgb = [50, 90, 230];
+pub const YELLOW: Rgb = [240, 200, 40];
+
+// Checked before anything else so recording costs a load when it is off
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+struct Recorder {
+    dir: String,
+    every: usize,
+    scale: usize,
+    offered: usize,
+    written: usize,
+    error: Option<String>,
+}
+
+// One picture of a simulation, drawn a pixel per cell
+#[derive(Clone, Debug)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn from_grid<T, F>(grid: &Grid<T>, colour: F) -> Self
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(_, cell)| colour(cell)).collect(),
+        }
+    }
+
+    // Anything outside the frame is dropped
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = colour;
+        }
+    }
+
+    // A binary PPM with every pixel blown up to a `scale` sided square
+    fn write_ppm(&self, out: &mut impl Write, scale: usize) -> std::io::Result<()> {
+        write!(
+            out,
+            "P6\n{} {}\n255\n",
+            self.width * scale,
+            self.height * scale
+        )?;
+        let mut row = Vec::with_capacity(self.width * scale * 3);
+        for y in 0..self.height {
+            row.clear();
+            for pixel in self.pixels[y * self.width..(y + 1) * self.width].iter() {
+                for _ in 0..scale {
+                    row.extend_from_slice(pixel);
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&row)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Starts writing frames to `dir` as frame_00000.ppm and so on, keeping one in `every` offered
+pub fn start(dir: &str, every: usize, scale: usize) -> Result<(), Error> {
+    std::fs::create_dir_all(dir).map_err(|e| Error::Runner(format!("{dir}: {e}")))?;
+    *RECORDER.lock().expect("frame recorder poisoned") = Some(Recorder {
+        dir: dir.into(),
+        every: every.max(1),
+        scale: scale.max(1),
+        offered: 0,
+        written: 0,
+        error: None,
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+// Stops recording and returns how many frames were written
+pub fn finish() -> Result<usize, Error> {
+    ENABLED.store(false, Ordering::Relaxed);
+    match RECORDER.lock().expect("frame recorder poisoned").take() {
+        Some(Recorder { error: Some(e), .. }) => Err(Error::Runner(e)),
+        Some(recorder) => Ok(recorder.written),
+        None => Ok(0),
+    }
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Offers a frame to the recorder.  `draw` is only called for frames which get written, so days
+// can call this every step of a simulation.
+#[inline]
+pub fn record<F>(draw: F)
+where
+    F: FnOnce() -> Frame,
+{
+    if enabled() {
+        record_enabled(draw);
+    }
+}
+
+fn record_enabled<F>(draw: F)
+where
+    F: FnOnce() -> Frame,
+{
+    let mut recorder = RECORDER.lock().expect("frame recorder poisoned");
+    let Some(recorder) = recorder.as_mut() else {
+        return;
+    };
+    recorder.offered += 1;
+    if recorder.error.is_some() || (recorder.offered - 1) % recorder.every != 0 {
+        return;
+    }
+
+    let path = format!("{}/frame_{:05}.ppm", recorder.dir, recorder.written);
+    let written = std::fs::File::create(&path).and_then(|file| {
+        let mut out = std::io::BufWriter::new(file);
+        draw().write_ppm(&mut out, recorder.scale)?;
+        out.flush()
+    });
+    match written {
+        Ok(()) => recorder.written += 1,
+        Err(e) => recorder.error = Some(format!("{path}: {e}")),
+    }
+}
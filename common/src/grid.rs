@@ -0,0 +1,320 @@
+use crate::rotation::Orientation;
+use helper::Error;
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+// Up, left, right and down, which is reading order for the cells around one
+pub const DIRS4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+// All eight surrounding cells in reading order
+pub const DIRS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// What a grid holds, read from and drawn as one character each
+pub trait Cell: Sized {
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(&self) -> char;
+}
+
+impl Cell for char {
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl Cell for u8 {
+    fn from_char(c: char) -> Option<Self> {
+        c.is_ascii().then_some(c as u8)
+    }
+
+    fn to_char(&self) -> char {
+        *self as char
+    }
+}
+
+// What lies beyond the edges of a grid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edge<T> {
+    // Nothing, so positions outside are never returned
+    Bounded,
+    // Leaving one side comes back in on the opposite one
+    Wrapping,
+    // Every cell outside holds this value, which reads but never writes see
+    Infinite(T),
+}
+
+// A rectangle of cells stored row by row.  Positions inside are `(x, y)` pairs of `usize`, which
+// indexing takes directly, while `get` and `step` take signed positions and apply the edge rules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    edge: Edge<T>,
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            cells: Vec::new(),
+            width: 0,
+            height: 0,
+            edge: Edge::Bounded,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+            edge: Edge::Bounded,
+        }
+    }
+
+    // One row per line, all of which must be the same length
+    pub fn parse<I, S>(lines: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        T: Cell,
+    {
+        let mut grid = Self::default();
+        for (y, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+            let start = grid.cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let cell = T::from_char(c)
+                    .ok_or_else(|| Error::InvalidInput(format!("Unexpected {c:?} at {x},{y}")))?;
+                grid.cells.push(cell);
+            }
+            let width = grid.cells.len() - start;
+            if y == 0 {
+                grid.width = width;
+            } else if width != grid.width {
+                return Err(Error::InvalidInput(format!(
+                    "Row {y} is {width} wide, expected {}",
+                    grid.width
+                )));
+            }
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    pub fn with_edge(self, edge: Edge<T>) -> Self {
+        Self { edge, ..self }
+    }
+
+    pub fn set_edge(&mut self, edge: Edge<T>) {
+        self.edge = edge;
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y)
+    }
+
+    // The stored cell a position refers to, if any
+    fn resolve(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        match self.edge {
+            Edge::Wrapping if self.width > 0 && self.height > 0 => Some((
+                x.rem_euclid(self.width as isize) as usize,
+                y.rem_euclid(self.height as isize) as usize,
+            )),
+            _ => self.contains(x, y).then_some((x as usize, y as usize)),
+        }
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        match (self.resolve(x, y), &self.edge) {
+            (Some(pos), _) => Some(&self[pos]),
+            (None, Edge::Infinite(background)) => Some(background),
+            (None, _) => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        let pos = self.resolve(x, y)?;
+        Some(&mut self[pos])
+    }
+
+    // The position one move from `pos`, if there is a stored cell there
+    pub fn step(&self, pos: (usize, usize), dir: (isize, isize)) -> Option<(usize, usize)> {
+        self.resolve(pos.0 as isize + dir.0, pos.1 as isize + dir.1)
+    }
+
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRS4.into_iter().filter_map(move |dir| self.step(pos, dir))
+    }
+
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRS8.into_iter().filter_map(move |dir| self.step(pos, dir))
+    }
+
+    // The positions along a row, column or diagonal going away from `pos`, stopping at the edge
+    // or, when wrapping, on getting back to `pos`
+    pub fn ray(
+        &self,
+        pos: (usize, usize),
+        dir: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut at = pos;
+        std::iter::from_fn(move || {
+            at = self.step(at, dir).filter(|next| *next != pos)?;
+            Some(at)
+        })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    // Every cell along with its position, in reading order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        let width = self.width;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    // The first position in reading order whose cell matches
+    pub fn position<F>(&self, f: F) -> Option<(usize, usize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let i = self.cells.iter().position(f)?;
+        Some((i % self.width, i / self.width))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(&f).collect(),
+            width: self.width,
+            height: self.height,
+            edge: match &self.edge {
+                Edge::Bounded => Edge::Bounded,
+                Edge::Wrapping => Edge::Wrapping,
+                Edge::Infinite(background) => Edge::Infinite(f(background)),
+            },
+        }
+    }
+
+    // The grid turned about its middle, which swaps the width and height if the axes swap
+    pub fn turned(&self, orientation: &Orientation<2>) -> Self
+    where
+        T: Clone,
+    {
+        let inverse = orientation.inverse();
+        let ([_, _], [width, height]) = orientation.apply_within([self.width, self.height], [0, 0]);
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let ([sx, sy], _) = inverse.apply_within([width, height], [x, y]);
+                cells.push(self.cells[sy * self.width + sx].clone());
+            }
+        }
+        Self {
+            cells,
+            width,
+            height,
+            edge: self.edge.clone(),
+        }
+    }
+
+    // Adds `by` cells of `fill` around every side, so what was at (x, y) moves to
+    // (x + by, y + by).  Infinite grids grow this way as their contents spread.
+    pub fn pad(&mut self, by: usize, fill: T)
+    where
+        T: Clone,
+    {
+        let width = self.width + 2 * by;
+        let height = self.height + 2 * by;
+        let mut cells = Vec::with_capacity(width * height);
+        cells.resize(by * width, fill.clone());
+        for row in self.cells.chunks(self.width.max(1)) {
+            cells.resize(cells.len() + by, fill.clone());
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + by, fill.clone());
+        }
+        cells.resize(width * height, fill);
+        self.cells = cells;
+        self.width = width;
+        self.height = height;
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Cell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    fmt,
+    ops::{Range, RangeInclusive},
+};
+
+// The integer types an interval set can hold
+pub trait Int: Copy + Ord + fmt::Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+    fn from_i128(v: i128) -> Option<Self>;
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(v: i128) -> Option<Self> {
+                    Self::try_from(v).ok()
+                }
+
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+// Half open and inclusive ranges, as the first and last value in them if there are any
+pub trait Span<T> {
+    fn span(&self) -> Option<(T, T)>;
+}
+
+impl<T: Int> Span<T> for Range<T> {
+    fn span(&self) -> Option<(T, T)> {
+        (self.start < self.end).then(|| (self.start, self.end.pred().expect("end above start")))
+    }
+}
+
+impl<T: Int> Span<T> for RangeInclusive<T> {
+    fn span(&self) -> Option<(T, T)> {
+        (self.start() <= self.end()).then_some((*self.start(), *self.end()))
+    }
+}
+
+// A set of integers kept as the runs of consecutive values in it.  Runs are stored by their
+// first and last value so a set can reach the largest value of its type, and are sorted with a
+// gap between each.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    spans: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { spans: Vec::new() }
+    }
+}
+
+impl<T: Int> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Int, R: Span<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    // How many values are in the set, which for a full u64 needs more than a u64
+    pub fn len(&self) -> u128 {
+        self.spans
+            .iter()
+            .map(|&(lo, hi)| (hi.to_i128() - lo.to_i128()) as u128 + 1)
+            .sum()
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.spans.first().map(|&(lo, _)| lo)
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.spans.last().map(|&(_, hi)| hi)
+    }
+
+    pub fn contains(&self, v: T) -> bool {
+        let i = self.spans.partition_point(|&(_, hi)| hi < v);
+        self.spans.get(i).is_some_and(|&(lo, _)| lo <= v)
+    }
+
+    // The runs in order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.spans.iter().map(|&(lo, hi)| lo..=hi)
+    }
+
+    pub fn insert(&mut self, range: impl Span<T>) {
+        let Some((mut lo, mut hi)) = range.span() else {
+            return;
+        };
+        // Runs touching the new one as well as those overlapping it are merged into it
+        let start = self
+            .spans
+            .partition_point(|&(_, h)| lo.pred().is_some_and(|p| h < p));
+        let end = self.spans.partition_point(|&(l, _)| match hi.succ() {
+            Some(s) => l <= s,
+            None => true,
+        });
+        if start < end {
+            lo = lo.min(self.spans[start].0);
+            hi = hi.max(self.spans[end - 1].1);
+        }
+        self.spans.splice(start..end, [(lo, hi)]);
+    }
+
+    pub fn remove(&mut self, range: impl Span<T>) {
+        let Some((lo, hi)) = range.span() else {
+            return;
+        };
+        let start = self.spans.partition_point(|&(_, h)| h < lo);
+        let end = self.spans.partition_point(|&(l, _)| l <= hi);
+        if start == end {
+            return;
+        }
+        // Only the first and last runs can stick out either side
+        let mut keep = Vec::new();
+        let (first, _) = self.spans[start];
+        if first < lo {
+            keep.push((first, lo.pred().expect("lo above a value")));
+        }
+        let (_, last) = self.spans[end - 1];
+        if last > hi {
+            keep.push((hi.succ().expect("hi below a value"), last));
+        }
+        self.spans.splice(start..end, keep);
+    }
+
+    // The values which are in both the set and `range`
+    pub fn clip(&self, range: impl Span<T>) -> Self {
+        let Some((lo, hi)) = range.span() else {
+            return Self::new();
+        };
+        let start = self.spans.partition_point(|&(_, h)| h < lo);
+        let end = self.spans.partition_point(|&(l, _)| l <= hi);
+        Self {
+            spans: self.spans[start..end]
+                .iter()
+                .map(|&(l, h)| (l.max(lo), h.min(hi)))
+                .collect(),
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        for &span in other.spans.iter() {
+            set.insert(span.0..=span.1);
+        }
+        set
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut spans = Vec::new();
+        for &(lo, hi) in other.spans.iter() {
+            spans.extend(self.clip(lo..=hi).spans);
+        }
+        Self { spans }
+    }
+
+    // The values below `at` and the rest
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let below = match at.pred() {
+            Some(before) => self.clip(T::MIN..=before),
+            None => Self::new(),
+        };
+        (below, self.clip(at..=T::MAX))
+    }
+
+    // Moves the values in each range by its offset, leaving those outside every range where
+    // they are.  A value in more than one range moves by the first one's offset, and values
+    // moved past either end of the type are dropped.
+    pub fn map_offsets<R, I>(&self, maps: I) -> Self
+    where
+        R: Span<T>,
+        I: IntoIterator<Item = (R, i128)>,
+    {
+        let mut rest = self.clone();
+        let mut moved = Self::new();
+        for (range, by) in maps {
+            let Some((lo, hi)) = range.span() else {
+                continue;
+            };
+            for (l, h) in rest.clip(lo..=hi).spans {
+                let l = l.to_i128().saturating_add(by).max(T::MIN.to_i128());
+                let h = h.to_i128().saturating_add(by).min(T::MAX.to_i128());
+                if l <= h {
+                    let l = T::from_i128(l).expect("clamped to the type");
+                    let h = T::from_i128(h).expect("clamped to the type");
+                    moved.insert(l..=h);
+                }
+            }
+            rest.remove(lo..=hi);
+        }
+        moved.union(&rest)
+    }
+
+    // The runs of values in `within` which are not in the set, in order
+    pub fn gaps(&self, within: impl Span<T>) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let bounds = within.span();
+        // The first value not yet looked at, which is None once past the end of the type
+        let mut at = bounds.map(|(lo, _)| lo);
+        let hi = bounds.map_or(T::MIN, |(_, hi)| hi);
+        let mut i = match bounds {
+            Some((lo, _)) => self.spans.partition_point(|&(_, h)| h < lo),
+            None => self.spans.len(),
+        };
+        std::iter::from_fn(move || loop {
+            let from = at.filter(|from| *from <= hi)?;
+            match self.spans.get(i) {
+                Some(&(l, h)) if l <= from => {
+                    at = h.succ();
+                    i += 1;
+                }
+                Some(&(l, _)) if l <= hi => {
+                    at = Some(l);
+                    return Some(from..=l.pred().expect("l above from"));
+                }
+                _ => {
+                    at = None;
+                    return Some(from..=hi);
+                }
+            }
+        })
+    }
+}
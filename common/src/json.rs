@@ -0,0 +1,318 @@
+use crate::cursor::Cursor;
+use helper::Error;
+use std::{fmt, str::FromStr};
+
+// A JSON document.  Object members keep their input order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(cursor: &mut Cursor) -> Result<Self, Error> {
+        cursor.skip_whitespace();
+        let value = match cursor.peek() {
+            Some(b'n') => Self::keyword(cursor, "null", Self::Null)?,
+            Some(b't') => Self::keyword(cursor, "true", Self::Bool(true))?,
+            Some(b'f') => Self::keyword(cursor, "false", Self::Bool(false))?,
+            Some(b'"') => Self::String(Self::parse_string(cursor)?),
+            Some(b'[') => {
+                cursor.bump();
+                let mut elements = Vec::new();
+                cursor.skip_whitespace();
+                if !cursor.eat(b']') {
+                    loop {
+                        elements.push(Self::parse(cursor)?);
+                        if cursor.eat(b']') {
+                            break;
+                        }
+                        cursor.expect(b',')?;
+                    }
+                }
+                Self::Array(elements)
+            }
+            Some(b'{') => {
+                cursor.bump();
+                let mut members = Vec::new();
+                cursor.skip_whitespace();
+                if !cursor.eat(b'}') {
+                    loop {
+                        cursor.skip_whitespace();
+                        let name = Self::parse_string(cursor)?;
+                        cursor.skip_whitespace();
+                        cursor.expect(b':')?;
+                        members.push((name, Self::parse(cursor)?));
+                        if cursor.eat(b'}') {
+                            break;
+                        }
+                        cursor.expect(b',')?;
+                    }
+                }
+                Self::Object(members)
+            }
+            Some(b'-' | b'0'..=b'9') => Self::Number(Self::parse_number(cursor)?),
+            _ => return Err(cursor.error("Expected a value")),
+        };
+        cursor.skip_whitespace();
+        Ok(value)
+    }
+
+    fn keyword(cursor: &mut Cursor, word: &str, value: Self) -> Result<Self, Error> {
+        let start = *cursor;
+        if cursor.eat_str(word) && !cursor.peek().is_some_and(|b| b.is_ascii_alphanumeric()) {
+            Ok(value)
+        } else {
+            Err(start.error(format!("Expected {word}")))
+        }
+    }
+
+    fn parse_number(cursor: &mut Cursor) -> Result<f64, Error> {
+        let start = *cursor;
+        cursor.eat(b'-');
+        if !cursor.eat(b'0') {
+            if !cursor.peek().is_some_and(|b| b.is_ascii_digit()) {
+                return Err(cursor.error("Expected a digit"));
+            }
+            cursor.take_while(|b| b.is_ascii_digit());
+        }
+        if cursor.eat(b'.') && cursor.take_while(|b| b.is_ascii_digit()).is_empty() {
+            return Err(cursor.error("Expected a digit"));
+        }
+        if cursor.eat(b'e') || cursor.eat(b'E') {
+            let _ = cursor.eat(b'+') || cursor.eat(b'-');
+            if cursor.take_while(|b| b.is_ascii_digit()).is_empty() {
+                return Err(cursor.error("Expected a digit"));
+            }
+        }
+        let len = cursor.pos() - start.pos();
+        start.rest()[..len]
+            .parse()
+            .map_err(|_| start.error("Bad number"))
+    }
+
+    fn parse_string(cursor: &mut Cursor) -> Result<String, Error> {
+        cursor.expect(b'"')?;
+        let mut s = String::new();
+        loop {
+            s.push_str(cursor.take_while(|b| b != b'"' && b != b'\\' && b >= 0x20));
+            let c = cursor.rest().chars().next();
+            match c {
+                Some('"') => {
+                    cursor.bump();
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    cursor.bump();
+                    s.push(Self::parse_escape(cursor)?);
+                }
+                Some(c) if c >= ' ' => {
+                    s.push(c);
+                    cursor.advance(c.len_utf8());
+                }
+                _ => return Err(cursor.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn parse_escape(cursor: &mut Cursor) -> Result<char, Error> {
+        let escape = *cursor;
+        Ok(match cursor.bump() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let hi = Self::parse_hex4(cursor)?;
+                let code = if (0xd800..0xdc00).contains(&hi) {
+                    // A surrogate pair
+                    if !cursor.eat_str("\\u") {
+                        return Err(cursor.error("Expected a low surrogate"));
+                    }
+                    let lo = Self::parse_hex4(cursor)?;
+                    if !(0xdc00..0xe000).contains(&lo) {
+                        return Err(escape.error("Bad surrogate pair"));
+                    }
+                    0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+                } else {
+                    hi
+                };
+                char::from_u32(code).ok_or_else(|| escape.error("Bad unicode escape"))?
+            }
+            _ => return Err(escape.error("Bad escape")),
+        })
+    }
+
+    fn parse_hex4(cursor: &mut Cursor) -> Result<u32, Error> {
+        let digits = cursor
+            .rest()
+            .get(..4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| cursor.error("Expected four hex digits"))?;
+        let code = u32::from_str_radix(digits, 16).map_err(|_| cursor.error("Bad hex digits"))?;
+        cursor.advance(4);
+        Ok(code)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    // Numbers which are whole and in range
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Number(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(63) => Some(*n as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Self::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Json)]> {
+        match self {
+            Self::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    // The first member of an object called `name`
+    pub fn get(&self, name: &str) -> Option<&Json> {
+        self.as_object()?
+            .iter()
+            .find_map(|(n, v)| (n == name).then_some(v))
+    }
+
+    // Whether this is an object with a member whose value is `value`
+    pub fn has_value(&self, value: &Json) -> bool {
+        self.as_object()
+            .is_some_and(|members| members.iter().any(|(_, v)| v == value))
+    }
+
+    // Every value in the document depth first, starting with this one.  Values for which `prune`
+    // is true are left out along with everything inside them.
+    pub fn walk<F>(&self, prune: F) -> Walk<'_, F>
+    where
+        F: Fn(&Json) -> bool,
+    {
+        Walk {
+            stack: vec![self],
+            prune,
+        }
+    }
+}
+
+pub struct Walk<'a, F> {
+    stack: Vec<&'a Json>,
+    prune: F,
+}
+
+impl<'a, F> Iterator for Walk<'a, F>
+where
+    F: Fn(&Json) -> bool,
+{
+    type Item = &'a Json;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let value = self.stack.pop()?;
+            if (self.prune)(value) {
+                continue;
+            }
+            match value {
+                Json::Array(elements) => self.stack.extend(elements.iter().rev()),
+                Json::Object(members) => self.stack.extend(members.iter().rev().map(|(_, v)| v)),
+                _ => {}
+            }
+            return Some(value);
+        }
+    }
+}
+
+impl FromStr for Json {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let json = Self::parse(&mut cursor)?;
+        cursor.finish()?;
+        Ok(json)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+            write!(f, "\"")?;
+            for c in s.chars() {
+                match c {
+                    '"' => write!(f, "\\\"")?,
+                    '\\' => write!(f, "\\\\")?,
+                    '\n' => write!(f, "\\n")?,
+                    '\r' => write!(f, "\\r")?,
+                    '\t' => write!(f, "\\t")?,
+                    c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+                    c => write!(f, "{c}")?,
+                }
+            }
+            write!(f, "\"")
+        }
+
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => write_string(f, s),
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                for (i, v) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (name, v)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{v}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
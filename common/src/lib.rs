@@ -0,0 +1,15 @@
+pub mod cancel;
+pub mod circuit;
+pub mod cuboid;
+pub mod cursor;
+pub mod cycle;
+pub mod dot;
+pub mod frames;
+pub mod grid;
+pub mod interval;
+pub mod json;
+pub mod md5;
+pub mod ocr;
+pub mod rotation;
+pub mod samples;
+pub mod search;
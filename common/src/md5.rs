@@ -0,0 +1,199 @@
+use helper::{Error, MD5String};
+use std::{
+    collections::VecDeque,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+// Worker threads used by every search, 0 meaning one per available core.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+// Most nonces a worker takes at a time.  Smaller ranges are split so every worker gets some.
+const CHUNK: usize = 4096;
+
+pub fn set_threads(n: usize) {
+    THREADS.store(n, Ordering::Relaxed);
+}
+
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+// Hashes a salt followed by a decimal nonce, optionally stretched by rehashing the lowercase hex
+// of the digest a number of extra times.
+#[derive(Default, Copy, Clone)]
+pub struct Md5Search {
+    salt: MD5String,
+    stretch: usize,
+}
+
+impl Md5Search {
+    pub fn new(salt: &str) -> Result<Self, Error> {
+        let salt: MD5String = salt.parse()?;
+        // Make sure the longest nonce still fits
+        let mut longest = salt;
+        longest.push_bytes(usize::MAX.to_string().as_bytes())?;
+        Ok(Self { salt, stretch: 0 })
+    }
+
+    pub fn stretch(self, rounds: usize) -> Self {
+        Self {
+            stretch: rounds,
+            ..self
+        }
+    }
+
+    pub fn digest(&self, nonce: usize) -> Result<[u8; 16], Error> {
+        let mut digits = [0; 20];
+        let mut i = digits.len();
+        let mut n = nonce;
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+
+        let mut md5 = self.salt;
+        md5.push_bytes(&digits[i..])?;
+        let mut digest = md5.digest();
+        if self.stretch > 0 {
+            let mut md5 = MD5String::default();
+            for _ in 0..self.stretch {
+                md5.truncate_without_zero(0);
+                md5.push_bytes(&hex(&digest))?;
+                digest = md5.digest();
+            }
+        }
+        Ok(digest)
+    }
+
+    // Calls `f` with the digest of every nonce in the range, spread over the worker threads, and
+    // returns the results in nonce order.
+    pub fn map<T, F>(&self, nonces: Range<usize>, f: F) -> Result<Vec<T>, Error>
+    where
+        T: Send,
+        F: Fn(usize, [u8; 16]) -> T + Sync,
+    {
+        Ok(self
+            .filter_map(nonces, |n, digest| Some(f(n, digest)))?
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect())
+    }
+
+    // Every nonce from `start` on for which `f` picks something out of the digest, in nonce
+    // order.  Nonces are hashed in batches, so stopping early wastes at most one batch.
+    pub fn find<T, F>(&self, start: usize, f: F) -> Matches<T, F>
+    where
+        T: Send,
+        F: Fn([u8; 16]) -> Option<T> + Sync,
+    {
+        Matches {
+            search: *self,
+            f,
+            next: start,
+            found: VecDeque::new(),
+        }
+    }
+
+    fn filter_map<T, F>(&self, nonces: Range<usize>, f: F) -> Result<Vec<(usize, T)>, Error>
+    where
+        T: Send,
+        F: Fn(usize, [u8; 16]) -> Option<T> + Sync,
+    {
+        // The workers cannot see the caller's token, so it is polled through a copy once a chunk
+        let token = crate::cancel::current();
+        let run = |range: Range<usize>| -> Result<Vec<(usize, T)>, Error> {
+            token.check()?;
+            let mut found = Vec::new();
+            for n in range {
+                if let Some(v) = f(n, self.digest(n)?) {
+                    found.push((n, v));
+                }
+            }
+            Ok(found)
+        };
+
+        let threads = threads();
+        let chunk = nonces.len().div_ceil(threads).clamp(1, CHUNK);
+        if threads == 1 || nonces.len() <= chunk {
+            return run(nonces);
+        }
+
+        let next = AtomicUsize::new(nonces.start);
+        let mut chunks = thread::scope(|s| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut chunks = Vec::new();
+                        loop {
+                            let start = next.fetch_add(chunk, Ordering::Relaxed);
+                            if start >= nonces.end {
+                                return Ok(chunks);
+                            }
+                            let end = (start + chunk).min(nonces.end);
+                            chunks.push((start, run(start..end)?));
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("MD5 worker panicked"))
+                .collect::<Result<Vec<Vec<_>>, Error>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        chunks.sort_unstable_by_key(|(start, _)| *start);
+        Ok(chunks.into_iter().flat_map(|(_, found)| found).collect())
+    }
+}
+
+pub struct Matches<T, F> {
+    search: Md5Search,
+    f: F,
+    next: usize,
+    found: VecDeque<(usize, T)>,
+}
+
+impl<T, F> Iterator for Matches<T, F>
+where
+    T: Send,
+    F: Fn([u8; 16]) -> Option<T> + Sync,
+{
+    type Item = Result<(usize, T), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.found.is_empty() {
+            let start = self.next;
+            self.next = start.checked_add(CHUNK * threads())?;
+            let f = &self.f;
+            match self
+                .search
+                .filter_map(start..self.next, |_, digest| f(digest))
+            {
+                Ok(found) => self.found.extend(found),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.found.pop_front().map(Ok)
+    }
+}
+
+pub fn hex(digest: &[u8; 16]) -> [u8; 32] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = [0; 32];
+    for (i, v) in digest.iter().enumerate() {
+        hex[i * 2] = DIGITS[(v >> 4) as usize];
+        hex[i * 2 + 1] = DIGITS[(v & 0xf) as usize];
+    }
+    hex
+}
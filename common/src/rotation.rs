@@ -0,0 +1,167 @@
+use helper::{HashMap, HashSet};
+
+// One way of turning N dimensional space about the origin onto itself, lining each axis up
+// with an axis, either way round.  Rotations keep the hand of the axes and reflections swap it.
+// Held as a matrix with one non-zero entry of 1 or -1 in each row and each column, applied to
+// points as columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Orientation<const N: usize>(pub [[isize; N]; N]);
+
+impl<const N: usize> Default for Orientation<N> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<const N: usize> Orientation<N> {
+    pub fn identity() -> Self {
+        Self(std::array::from_fn(|r| {
+            std::array::from_fn(|c| (r == c) as isize)
+        }))
+    }
+
+    // Every orientation, starting with the identity: 8 in 2D and 48 in 3D
+    pub fn all() -> Vec<Self> {
+        let mut all = Vec::new();
+        let mut axes: [usize; N] = std::array::from_fn(|a| a);
+        permutations(&mut axes, 0, &mut |axes| {
+            for signs in 0..1 << N {
+                all.push(Self(std::array::from_fn(|r| {
+                    std::array::from_fn(|c| match c == axes[r] {
+                        true if signs >> r & 1 == 1 => -1,
+                        true => 1,
+                        false => 0,
+                    })
+                })));
+            }
+        });
+        all
+    }
+
+    // Only the rotations, starting with the identity: 4 in 2D and 24 in 3D
+    pub fn rotations() -> Vec<Self> {
+        Self::all().into_iter().filter(|o| o.det() == 1).collect()
+    }
+
+    // 1 for rotations and -1 for reflections: the signs of the entries, flipped once more for
+    // each pair of rows whose columns are the other way round
+    pub fn det(&self) -> isize {
+        let mut det = 1;
+        let mut used = [false; N];
+        for row in self.0.iter() {
+            let Some(c) = row.iter().position(|&v| v != 0) else {
+                return 0;
+            };
+            det *= row[c];
+            if used[c + 1..].iter().filter(|&&u| u).count() % 2 == 1 {
+                det = -det;
+            }
+            used[c] = true;
+        }
+        det
+    }
+
+    // Turning by `other` and then by `self`
+    pub fn compose(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|r| {
+            std::array::from_fn(|c| (0..N).map(|k| self.0[r][k] * other.0[k][c]).sum())
+        }))
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self(std::array::from_fn(|r| {
+            std::array::from_fn(|c| self.0[c][r])
+        }))
+    }
+
+    pub fn apply(&self, p: [isize; N]) -> [isize; N] {
+        std::array::from_fn(|r| (0..N).map(|c| self.0[r][c] * p[c]).sum())
+    }
+
+    pub fn apply_all(&self, points: &[[isize; N]]) -> Vec<[isize; N]> {
+        points.iter().map(|&p| self.apply(p)).collect()
+    }
+
+    // Where a cell of a box `size` cells across ends up when the box is turned about its middle
+    // and put back with its lowest corner on the origin, along with the size it turns into.
+    // Positions are doubled around the middle so boxes an even number of cells across still
+    // turn on whole numbers.
+    pub fn apply_within(&self, size: [usize; N], p: [usize; N]) -> ([usize; N], [usize; N]) {
+        let turned_size = self
+            .apply(size.map(|s| s as isize))
+            .map(|s| s.unsigned_abs());
+        let doubled = std::array::from_fn(|a| 2 * p[a] as isize - (size[a] as isize - 1));
+        let turned = self.apply(doubled);
+        let p = std::array::from_fn(|a| ((turned[a] + turned_size[a] as isize - 1) / 2) as usize);
+        (p, turned_size)
+    }
+}
+
+impl Orientation<2> {
+    // A square of cells indexed by row then column, turned about its middle
+    pub fn apply_square<T: Copy, const S: usize>(&self, square: &[[T; S]; S]) -> [[T; S]; S] {
+        let inverse = self.inverse();
+        std::array::from_fn(|y| {
+            std::array::from_fn(|x| {
+                let ([x, y], _) = inverse.apply_within([S, S], [x, y]);
+                square[y][x]
+            })
+        })
+    }
+}
+
+// Calls `f` with each ordering of `items`, swapping each of the rest into place `k` in turn
+fn permutations<T, F: FnMut(&[T])>(items: &mut [T], k: usize, f: &mut F) {
+    if k == items.len() {
+        f(items);
+        return;
+    }
+    for i in k..items.len() {
+        items.swap(k, i);
+        permutations(items, k + 1, f);
+        items.swap(k, i);
+    }
+}
+
+// How to turn and then move points to line them up with another set of points
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alignment<const N: usize> {
+    pub orientation: Orientation<N>,
+    pub offset: [isize; N],
+}
+
+impl<const N: usize> Alignment<N> {
+    pub fn apply(&self, p: [isize; N]) -> [isize; N] {
+        let turned = self.orientation.apply(p);
+        std::array::from_fn(|a| turned[a] + self.offset[a])
+    }
+}
+
+// Finds the first of `orientations` and the offset after it which moves at least `min_matches`
+// of `points` onto `target`.  Every pair of a turned point and a target point votes for the
+// offset between them, and an offset gets one vote per point it lines up.
+pub fn align<const N: usize>(
+    orientations: &[Orientation<N>],
+    points: &[[isize; N]],
+    target: &HashSet<[isize; N]>,
+    min_matches: usize,
+) -> Option<Alignment<N>> {
+    let mut votes: HashMap<[isize; N], usize> = HashMap::default();
+    for &orientation in orientations {
+        votes.clear();
+        for p in orientation.apply_all(points) {
+            for t in target.iter() {
+                let offset = std::array::from_fn(|a| t[a] - p[a]);
+                let count = votes.entry(offset).or_default();
+                *count += 1;
+                if *count >= min_matches {
+                    return Some(Alignment {
+                        orientation,
+                        offset,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,191 @@
+use helper::NewRunner;
+use std::collections::BTreeMap;
+
+pub type Register = fn(&mut BTreeMap<(usize, usize), (u8, NewRunner)>);
+
+// A worked example from the puzzle text.  Sample files hold any number of these, each starting
+// with a header line giving the part and expected answer:
+//
+//     === part 1: 24000
+//     <input lines>
+#[derive(Debug)]
+pub struct Sample {
+    pub part: u8,
+    pub expected: String,
+    pub input: String,
+}
+
+const HEADER: &str = "=== part ";
+
+pub fn parse(file: &str) -> Result<Vec<Sample>, String> {
+    let mut samples: Vec<Sample> = Vec::new();
+    for line in file.lines() {
+        if let Some(header) = line.strip_prefix(HEADER) {
+            let (part, expected) = header
+                .split_once(':')
+                .ok_or_else(|| format!("Bad sample header {line:?}"))?;
+            let part = part
+                .trim()
+                .parse()
+                .map_err(|_| format!("Bad sample header {line:?}"))?;
+            samples.push(Sample {
+                part,
+                expected: expected.trim().into(),
+                input: String::new(),
+            });
+        } else if let Some(sample) = samples.last_mut() {
+            sample.input.push_str(line);
+            sample.input.push('\n');
+        } else if !line.is_empty() {
+            return Err(format!("Input before the first sample header: {line:?}"));
+        }
+    }
+    // Blank lines separating samples are not part of the input
+    for sample in samples.iter_mut() {
+        sample
+            .input
+            .truncate(sample.input.trim_end_matches('\n').len());
+        sample.input.push('\n');
+    }
+    Ok(samples)
+}
+
+// Runs the samples for one part of a day, read from `samples/<day>.txt` under the crate
+// directory, and returns a description of each one which did not give the expected answer.
+// Days without samples for the part pass, as not every puzzle has a usable example.
+pub fn check(
+    register: Register,
+    dir: &str,
+    year: usize,
+    day: usize,
+    part: u8,
+) -> Result<(), String> {
+    let path = format!("{dir}/samples/{day:02}.txt");
+    let file = match std::fs::read_to_string(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("{path}: {e}")),
+    };
+    let samples: Vec<Sample> = parse(&file)
+        .map_err(|e| format!("{path}: {e}"))?
+        .into_iter()
+        .filter(|sample| sample.part == part)
+        .collect();
+    if samples.is_empty() {
+        return Ok(());
+    }
+
+    let mut runners = BTreeMap::new();
+    register(&mut runners);
+    let Some((parts, new)) = runners.get(&(year, day)) else {
+        return Err(format!("{path}: {year} day {day} is not registered"));
+    };
+    if part > *parts {
+        return Err(format!("{path}: {year} day {day} has no part {part}"));
+    }
+
+    let mut failures = Vec::new();
+    for (i, sample) in samples.iter().enumerate() {
+        let mut runner = new();
+        let answer = runner
+            .parse(sample.input.as_bytes(), part)
+            .and_then(|_| runner.run_part(part));
+        match answer {
+            Ok(answer) if answer.to_string() == sample.expected => {}
+            Ok(answer) => failures.push(format!(
+                "sample {}: expected {:?}, got {:?}",
+                i + 1,
+                sample.expected,
+                answer.to_string()
+            )),
+            Err(e) => failures.push(format!("sample {}: {e:?}", i + 1)),
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{year} day {day} part {part}\n{}",
+            failures.join("\n")
+        ))
+    }
+}
+
+// Declares a test for both parts of every day of a year, checking each against its sample file
+// if it has one:
+//
+//     common::sample_tests!(aoc_2022::register, 2022);
+#[macro_export]
+macro_rules! sample_tests {
+    ($register:path, $year:literal) => {
+        $crate::sample_tests! {
+            @days $register, $year;
+            day_01_part_1 => 1, 1;
+            day_01_part_2 => 1, 2;
+            day_02_part_1 => 2, 1;
+            day_02_part_2 => 2, 2;
+            day_03_part_1 => 3, 1;
+            day_03_part_2 => 3, 2;
+            day_04_part_1 => 4, 1;
+            day_04_part_2 => 4, 2;
+            day_05_part_1 => 5, 1;
+            day_05_part_2 => 5, 2;
+            day_06_part_1 => 6, 1;
+            day_06_part_2 => 6, 2;
+            day_07_part_1 => 7, 1;
+            day_07_part_2 => 7, 2;
+            day_08_part_1 => 8, 1;
+            day_08_part_2 => 8, 2;
+            day_09_part_1 => 9, 1;
+            day_09_part_2 => 9, 2;
+            day_10_part_1 => 10, 1;
+            day_10_part_2 => 10, 2;
+            day_11_part_1 => 11, 1;
+            day_11_part_2 => 11, 2;
+            day_12_part_1 => 12, 1;
+            day_12_part_2 => 12, 2;
+            day_13_part_1 => 13, 1;
+            day_13_part_2 => 13, 2;
+            day_14_part_1 => 14, 1;
+            day_14_part_2 => 14, 2;
+            day_15_part_1 => 15, 1;
+            day_15_part_2 => 15, 2;
+            day_16_part_1 => 16, 1;
+            day_16_part_2 => 16, 2;
+            day_17_part_1 => 17, 1;
+            day_17_part_2 => 17, 2;
+            day_18_part_1 => 18, 1;
+            day_18_part_2 => 18, 2;
+            day_19_part_1 => 19, 1;
+            day_19_part_2 => 19, 2;
+            day_20_part_1 => 20, 1;
+            day_20_part_2 => 20, 2;
+            day_21_part_1 => 21, 1;
+            day_21_part_2 => 21, 2;
+            day_22_part_1 => 22, 1;
+            day_22_part_2 => 22, 2;
+            day_23_part_1 => 23, 1;
+            day_23_part_2 => 23, 2;
+            day_24_part_1 => 24, 1;
+            day_24_part_2 => 24, 2;
+            day_25_part_1 => 25, 1;
+            day_25_part_2 => 25, 2;
+        }
+    };
+    (@days $register:path, $year:literal; $($name:ident => $day:literal, $part:literal;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                if let Err(e) = $crate::samples::check(
+                    $register,
+                    env!("CARGO_MANIFEST_DIR"),
+                    $year,
+                    $day,
+                    $part,
+                ) {
+                    panic!("{e}");
+                }
+            }
+        )*
+    };
+}
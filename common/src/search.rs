@@ -0,0 +1,324 @@
+use helper::{Error, HashMap, HashSet};
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+};
+
+// A node in a state space.  States with the same key are treated as the same node, so the key
+// can be a canonical form folding together states which only differ by some symmetry.
+pub trait SearchState: Clone {
+    type Context: ?Sized;
+    type Key: Hash + Eq;
+
+    fn key(&self) -> Self::Key;
+
+    // Adds every state one move away along with the cost of the move.  The breadth first
+    // searches take every move to cost 1.
+    fn neighbors(&self, ctx: &mut Self::Context, out: &mut Vec<(Self, usize)>);
+
+    fn is_goal(&self, ctx: &Self::Context) -> bool;
+
+    // A lower bound on the cost of reaching a goal, which only A* uses
+    fn heuristic(&self, _ctx: &Self::Context) -> usize {
+        0
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Stats {
+    // Distinct states reached
+    pub visited: usize,
+    // States whose neighbors were generated
+    pub expanded: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Found<S> {
+    pub cost: usize,
+    // Every state from the start to the goal, both included
+    pub path: Vec<S>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Outcome<S> {
+    pub found: Option<Found<S>>,
+    pub stats: Stats,
+}
+
+impl<S> Outcome<S> {
+    pub fn cost(&self) -> Result<usize, Error> {
+        self.found
+            .as_ref()
+            .map(|found| found.cost)
+            .ok_or(Error::Unsolved)
+    }
+}
+
+const NO_PARENT: usize = usize::MAX;
+
+struct Node<S> {
+    state: S,
+    parent: usize,
+    cost: usize,
+    stale: bool,
+}
+
+// Every state reached, each pointing back at the one it was reached from
+struct Tree<S> {
+    nodes: Vec<Node<S>>,
+}
+
+impl<S: Clone> Tree<S> {
+    fn new(start: S) -> Self {
+        Self {
+            nodes: vec![Node {
+                state: start,
+                parent: NO_PARENT,
+                cost: 0,
+                stale: false,
+            }],
+        }
+    }
+
+    fn push(&mut self, state: S, parent: usize, cost: usize) -> usize {
+        self.nodes.push(Node {
+            state,
+            parent,
+            cost,
+            stale: false,
+        });
+        self.nodes.len() - 1
+    }
+
+    // The states from the root to `node`
+    fn path(&self, mut node: usize) -> Vec<S> {
+        let mut path = Vec::new();
+        while node != NO_PARENT {
+            path.push(self.nodes[node].state.clone());
+            node = self.nodes[node].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn found(&self, node: usize) -> Option<Found<S>> {
+        Some(Found {
+            cost: self.nodes[node].cost,
+            path: self.path(node),
+        })
+    }
+}
+
+// Searches from a start state using whatever the states need to find their neighbors:
+//
+//     let cost = Search::new(&mut cave).astar(start).cost()?;
+pub struct Search<'a, C: ?Sized> {
+    ctx: &'a mut C,
+    limit: usize,
+}
+
+impl<'a, C: ?Sized> Search<'a, C> {
+    pub fn new(ctx: &'a mut C) -> Self {
+        Self {
+            ctx,
+            limit: usize::MAX,
+        }
+    }
+
+    // Stops following paths costing more than `max`
+    pub fn limit(self, max: usize) -> Self {
+        Self { limit: max, ..self }
+    }
+
+    pub fn bfs<S>(&mut self, start: S) -> Outcome<S>
+    where
+        S: SearchState<Context = C>,
+    {
+        let mut stats = Stats::default();
+        let mut seen = HashSet::default();
+        seen.insert(start.key());
+        let mut tree = Tree::new(start);
+        let mut queue = VecDeque::from([0]);
+        let mut out = Vec::new();
+
+        let mut found = None;
+        if tree.nodes[0].state.is_goal(self.ctx) {
+            found = tree.found(0);
+        }
+        while found.is_none() {
+            let Some(node) = queue.pop_front() else {
+                break;
+            };
+            let cost = tree.nodes[node].cost + 1;
+            if cost > self.limit {
+                continue;
+            }
+            stats.expanded += 1;
+            tree.nodes[node].state.neighbors(self.ctx, &mut out);
+            for (next, _) in out.drain(..) {
+                if !seen.insert(next.key()) {
+                    continue;
+                }
+                let is_goal = next.is_goal(self.ctx);
+                let next = tree.push(next, node, cost);
+                if is_goal {
+                    found = tree.found(next);
+                    break;
+                }
+                queue.push_back(next);
+            }
+        }
+
+        stats.visited = seen.len();
+        Outcome { found, stats }
+    }
+
+    pub fn dijkstra<S>(&mut self, start: S) -> Outcome<S>
+    where
+        S: SearchState<Context = C>,
+    {
+        self.best_first(start, false)
+    }
+
+    pub fn astar<S>(&mut self, start: S) -> Outcome<S>
+    where
+        S: SearchState<Context = C>,
+    {
+        self.best_first(start, true)
+    }
+
+    fn best_first<S>(&mut self, start: S, use_heuristic: bool) -> Outcome<S>
+    where
+        S: SearchState<Context = C>,
+    {
+        let mut stats = Stats::default();
+        let mut best = HashMap::default();
+        best.insert(start.key(), 0);
+        let mut tree = Tree::new(start);
+        let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+        let mut out = Vec::new();
+
+        let mut found = None;
+        while let Some(Reverse((_, node))) = heap.pop() {
+            // A cheaper way here turned up after this was queued
+            if tree.nodes[node].stale {
+                continue;
+            }
+            if tree.nodes[node].state.is_goal(self.ctx) {
+                found = tree.found(node);
+                break;
+            }
+            stats.expanded += 1;
+            let cost = tree.nodes[node].cost;
+            tree.nodes[node].state.neighbors(self.ctx, &mut out);
+            for (next, step) in out.drain(..) {
+                let cost = cost + step;
+                if cost > self.limit {
+                    continue;
+                }
+                let estimate = if use_heuristic {
+                    cost + next.heuristic(self.ctx)
+                } else {
+                    cost
+                };
+                match best.entry(next.key()) {
+                    Entry::Occupied(mut e) => {
+                        let old = *e.get();
+                        if tree.nodes[old].cost <= cost {
+                            continue;
+                        }
+                        tree.nodes[old].stale = true;
+                        e.insert(tree.push(next, node, cost));
+                    }
+                    Entry::Vacant(e) => {
+                        e.insert(tree.push(next, node, cost));
+                    }
+                }
+                heap.push(Reverse((estimate, tree.nodes.len() - 1)));
+            }
+        }
+
+        stats.visited = best.len();
+        Outcome { found, stats }
+    }
+
+    // Breadth first from both ends at once, always growing the smaller frontier by a whole
+    // level.  Moves must be reversible with every neighbor of a state having it as a neighbor
+    // too, and `is_goal` is not used.
+    pub fn bidirectional<S>(&mut self, start: S, goal: S) -> Outcome<S>
+    where
+        S: SearchState<Context = C>,
+    {
+        let mut stats = Stats::default();
+        let mut sides = [start, goal].map(|state| {
+            let mut seen = HashMap::default();
+            seen.insert(state.key(), 0);
+            (Tree::new(state), seen, vec![0])
+        });
+        let mut out = Vec::new();
+
+        let mut found = None;
+        if sides[0].1.contains_key(&sides[1].0.nodes[0].state.key()) {
+            found = sides[0].0.found(0);
+        }
+        let mut depth = 0;
+        while found.is_none() && depth < self.limit {
+            let side = if sides[0].2.len() <= sides[1].2.len() {
+                0
+            } else {
+                1
+            };
+            let [a, b] = &mut sides;
+            let ((tree, seen, frontier), other) = if side == 0 { (a, b) } else { (b, a) };
+            if frontier.is_empty() {
+                break;
+            }
+
+            // Finish the whole level before picking the cheapest meeting point, as the other
+            // side's states are not all the same distance from its end
+            let mut meet: Option<(usize, usize, usize)> = None;
+            for node in std::mem::take(frontier) {
+                stats.expanded += 1;
+                let cost = tree.nodes[node].cost + 1;
+                tree.nodes[node].state.neighbors(self.ctx, &mut out);
+                for (next, _) in out.drain(..) {
+                    let key = next.key();
+                    if seen.contains_key(&key) {
+                        continue;
+                    }
+                    let next = tree.push(next, node, cost);
+                    if let Some(&theirs) = other.1.get(&key) {
+                        let total = cost + other.0.nodes[theirs].cost;
+                        if !matches!(meet, Some((best, _, _)) if best <= total) {
+                            meet = Some((total, next, theirs));
+                        }
+                    }
+                    seen.insert(key, next);
+                    frontier.push(next);
+                }
+            }
+            depth += 1;
+
+            if let Some((cost, ours, theirs)) = meet {
+                let (forward, backward) = if side == 0 {
+                    (ours, theirs)
+                } else {
+                    (theirs, ours)
+                };
+                // The meeting state comes from the forward side, followed by the rest of the
+                // backward side's path back to the goal
+                let mut path = sides[0].0.path(forward);
+                let mut node = sides[1].0.nodes[backward].parent;
+                while node != NO_PARENT {
+                    path.push(sides[1].0.nodes[node].state.clone());
+                    node = sides[1].0.nodes[node].parent;
+                }
+                found = Some(Found { cost, path });
+            }
+        }
+
+        stats.visited = sides[0].1.len() + sides[1].1.len();
+        Outcome { found, stats }
+    }
+}
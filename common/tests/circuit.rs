@@ -0,0 +1,57 @@
+use common::circuit::Circuit;
+use helper::Error;
+
+fn circuit(width: u32, gates: &[&str]) -> Circuit {
+    let mut circuit = Circuit::new(width).unwrap();
+    for gate in gates {
+        circuit.parse_gate(gate).unwrap();
+    }
+    circuit
+}
+
+#[test]
+fn widths_mask_values() {
+    let c = circuit(16, &["123 -> x", "NOT x -> y", "x LSHIFT 15 -> z"]);
+    let values = c.eval().unwrap();
+    assert_eq!(values[c.id("y").unwrap()], 65412);
+    assert_eq!(values[c.id("z").unwrap()], 0x8000);
+
+    let c = circuit(64, &["0 -> x", "NOT x -> y"]);
+    assert_eq!(c.eval().unwrap()[c.id("y").unwrap()], u64::MAX);
+    let c = circuit(1, &["0 -> x", "NOT x -> y"]);
+    assert_eq!(c.eval().unwrap()[c.id("y").unwrap()], 1);
+}
+
+#[test]
+fn rejects_widths_outside_1_to_64() {
+    for width in [0, 65, u32::MAX] {
+        assert!(
+            matches!(Circuit::new(width), Err(Error::InvalidInput(_))),
+            "{width}"
+        );
+    }
+}
+
+#[test]
+fn bus_skips_wires_without_bit_numbers() {
+    let c = circuit(
+        1,
+        &[
+            "1 -> z00", "0 -> z01", "1 -> z02", "1 -> zbc", "1 -> z", "0 -> z1x",
+        ],
+    );
+    assert_eq!(c.bus(&c.eval().unwrap(), 'z').unwrap(), 5);
+
+    let c = circuit(1, &["1 -> x63", "1 -> x00"]);
+    assert_eq!(c.bus(&c.eval().unwrap(), 'x').unwrap(), 1 << 63 | 1);
+    let c = circuit(1, &["1 -> x64"]);
+    assert!(matches!(
+        c.bus(&c.eval().unwrap(), 'x'),
+        Err(Error::InvalidInput(_))
+    ));
+    let c = circuit(1, &["1 -> x99999999999"]);
+    assert!(matches!(
+        c.bus(&c.eval().unwrap(), 'x'),
+        Err(Error::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,164 @@
+use common::cuboid::{Cuboid, CuboidSet, Strategy};
+use std::collections::HashSet;
+
+const STRATEGIES: [Strategy; 2] = [Strategy::Fragments, Strategy::InclusionExclusion];
+
+fn cuboid<const N: usize>(min: [isize; N], max: [isize; N]) -> Cuboid<N> {
+    Cuboid::new(min, max).unwrap()
+}
+
+// The cells of a small set, one by one
+fn cells(set: &CuboidSet<3>) -> HashSet<[isize; 3]> {
+    let mut cells = HashSet::new();
+    for x in -2..16 {
+        for y in -2..16 {
+            for z in -2..16 {
+                if set.contains([x, y, z]) {
+                    cells.insert([x, y, z]);
+                }
+            }
+        }
+    }
+    cells
+}
+
+fn surface_area(cells: &HashSet<[isize; 3]>) -> usize {
+    let mut area = 0;
+    for c in cells.iter() {
+        for a in 0..3 {
+            for by in [-1, 1] {
+                let mut n = *c;
+                n[a] += by;
+                if !cells.contains(&n) {
+                    area += 1;
+                }
+            }
+        }
+    }
+    area
+}
+
+// Random boxes inside 0..10 from a fixed seed
+struct Boxes(u64);
+
+impl Iterator for Boxes {
+    type Item = Cuboid<3>;
+
+    fn next(&mut self) -> Option<Cuboid<3>> {
+        let mut next = |below: u64| {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) % below) as isize
+        };
+        let min: [isize; 3] = std::array::from_fn(|_| next(10));
+        let max = std::array::from_fn(|a| min[a] + next(5));
+        Cuboid::new(min, max)
+    }
+}
+
+#[test]
+fn single_cuboids() {
+    let c = cuboid([0, 0, 0], [1, 2, 3]);
+    assert_eq!(c.volume(), 24);
+    assert_eq!(c.surface_area(), 2 * (12 + 8 + 6));
+    assert!(Cuboid::new([0, 0], [1, -1]).is_none());
+    assert_eq!(cuboid([5], [5]).surface_area(), 2);
+
+    let hole = cuboid([1, 1, 1], [1, 1, 1]);
+    let pieces = cuboid([0, 0, 0], [2, 2, 2]).subtract(&hole);
+    assert_eq!(pieces.len(), 6);
+    assert_eq!(pieces.iter().map(|p| p.volume()).sum::<u128>(), 26);
+    assert_eq!(hole.subtract(&c), []);
+    assert_eq!(
+        c.intersect(&cuboid([1, -5, 3], [9, 0, 9])),
+        Some(cuboid([1, 0, 3], [1, 0, 3]))
+    );
+}
+
+#[test]
+fn inserting_and_removing_matches_cells() {
+    for strategy in STRATEGIES {
+        let mut set = CuboidSet::new(strategy);
+        let mut expected = HashSet::new();
+        for (i, c) in Boxes(1).take(60).enumerate() {
+            let c_cells = cells(&{
+                let mut only = CuboidSet::new(strategy);
+                only.insert(c);
+                only
+            });
+            if i % 3 == 2 {
+                set.remove(c);
+                expected.retain(|p| !c_cells.contains(p));
+            } else {
+                set.insert(c);
+                expected.extend(c_cells);
+            }
+            assert_eq!(
+                set.volume(),
+                expected.len() as u128,
+                "{strategy:?} after {i}"
+            );
+        }
+        assert_eq!(cells(&set), expected, "{strategy:?}");
+        assert_eq!(
+            set.surface_area(),
+            surface_area(&expected) as u128,
+            "{strategy:?}"
+        );
+    }
+}
+
+#[test]
+fn set_operations_match_cells() {
+    let mut boxes = Boxes(7);
+    for lhs in STRATEGIES {
+        for rhs in STRATEGIES {
+            let mut a = CuboidSet::new(lhs);
+            let mut b = CuboidSet::new(rhs);
+            for c in boxes.by_ref().take(8) {
+                a.insert(c);
+            }
+            for c in boxes.by_ref().take(8) {
+                b.insert(c);
+            }
+            let (a_cells, b_cells) = (cells(&a), cells(&b));
+
+            let union = a.union(&b);
+            assert_eq!(cells(&union), &a_cells | &b_cells, "{lhs:?} | {rhs:?}");
+            let intersection = a.intersection(&b);
+            assert_eq!(
+                cells(&intersection),
+                &a_cells & &b_cells,
+                "{lhs:?} & {rhs:?}"
+            );
+            let difference = a.difference(&b);
+            assert_eq!(cells(&difference), &a_cells - &b_cells, "{lhs:?} - {rhs:?}");
+
+            for set in [union, intersection, difference] {
+                assert_eq!(set.volume(), cells(&set).len() as u128);
+                assert_eq!(set.surface_area(), surface_area(&cells(&set)) as u128);
+            }
+        }
+    }
+}
+
+#[test]
+fn clipping_and_mixed_strategies() {
+    let mut fragments = CuboidSet::new(Strategy::Fragments);
+    fragments.insert(cuboid([0, 0], [9, 9]));
+    let mut counted = CuboidSet::new(Strategy::InclusionExclusion);
+    counted.insert(cuboid([5, 5], [14, 14]));
+
+    assert_eq!(fragments.clip(&cuboid([8, -3], [20, 3])).volume(), 8);
+    assert_eq!(fragments.union(&fragments).strategy(), Strategy::Fragments);
+    assert_eq!(
+        fragments.union(&counted).strategy(),
+        Strategy::InclusionExclusion
+    );
+    assert_eq!(fragments.union(&counted).volume(), 175);
+    assert_eq!(counted.difference(&fragments).volume(), 75);
+    assert!(fragments.difference(&fragments).is_empty());
+    assert!(counted.intersection(&fragments).contains([9, 9]));
+}
@@ -0,0 +1,54 @@
+use common::cycle::{self, Cycle, Detector};
+
+// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3, a tail of three steps into a loop of four
+fn step(x: &mut usize) {
+    *x = if *x == 6 { 3 } else { *x + 1 };
+}
+
+const RHO: Cycle = Cycle { start: 3, len: 4 };
+
+#[test]
+fn brent_finds_the_loop() {
+    assert_eq!(cycle::brent(0, step).unwrap(), RHO);
+    assert_eq!(cycle::brent(4, step).unwrap(), Cycle { start: 0, len: 4 });
+}
+
+#[test]
+fn floyd_finds_the_loop() {
+    assert_eq!(cycle::floyd(0, step).unwrap(), RHO);
+    assert_eq!(cycle::floyd(4, step).unwrap(), Cycle { start: 0, len: 4 });
+}
+
+#[test]
+fn detector_finds_the_loop() {
+    let mut detector = Detector::new();
+    let mut state = 0;
+    let cycle = loop {
+        if let Some(cycle) = detector.push(state) {
+            break cycle;
+        }
+        step(&mut state);
+    };
+    assert_eq!(cycle, RHO);
+
+    let history = cycle::find(0, step).unwrap();
+    assert_eq!(history.cycle, RHO);
+    assert_eq!(history.states, [0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(*history.nth(1000), 3 + (1000 - 3) % 4);
+}
+
+#[test]
+fn extrapolate_matches_brute_force() {
+    // Each step adds the square of the state, so the total grows by 86 every lap
+    let mut totals = vec![0u64];
+    let mut state = 0;
+    for _ in 0..1000 {
+        totals.push(totals.last().unwrap() + (state * state) as u64);
+        step(&mut state);
+    }
+    let known = &totals[..=RHO.start + RHO.len];
+    for n in 0..totals.len() {
+        assert_eq!(RHO.extrapolate(n, known).unwrap(), totals[n], "step {n}");
+    }
+    assert!(RHO.extrapolate(10, &totals[..RHO.start + RHO.len]).is_err());
+}
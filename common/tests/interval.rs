@@ -0,0 +1,86 @@
+use common::interval::IntervalSet;
+
+fn runs<T: common::interval::Int>(set: &IntervalSet<T>) -> Vec<(T, T)> {
+    set.iter().map(|r| (*r.start(), *r.end())).collect()
+}
+
+#[test]
+fn insert_merges_overlapping_and_touching_runs() {
+    let mut set = IntervalSet::new();
+    set.insert(10..20);
+    set.insert(30..=39);
+    assert_eq!(runs(&set), [(10, 19), (30, 39)]);
+    set.insert(20..25);
+    assert_eq!(runs(&set), [(10, 24), (30, 39)]);
+    set.insert(26..30);
+    assert_eq!(runs(&set), [(10, 24), (26, 39)]);
+    set.insert(0..100);
+    assert_eq!(runs(&set), [(0, 99)]);
+    set.insert(5..5);
+    assert_eq!(set.len(), 100);
+}
+
+#[test]
+fn remove_splits_runs() {
+    let mut set: IntervalSet<i32> = [-10..=10].into_iter().collect();
+    set.remove(-2..3);
+    assert_eq!(runs(&set), [(-10, -3), (3, 10)]);
+    set.remove(-20..=-10);
+    set.remove(10..=20);
+    assert_eq!(runs(&set), [(-9, -3), (3, 9)]);
+    set.remove(-5..=5);
+    assert_eq!(runs(&set), [(-9, -6), (6, 9)]);
+    assert!(set.contains(-6));
+    assert!(!set.contains(0));
+}
+
+#[test]
+fn reaches_the_ends_of_the_type() {
+    let mut set: IntervalSet<u32> = IntervalSet::new();
+    set.insert(0..=u32::MAX);
+    assert_eq!(set.len(), 1 << 32);
+    set.remove(5..=8);
+    set.remove(u32::MAX..=u32::MAX);
+    assert_eq!(runs(&set), [(0, 4), (9, u32::MAX - 1)]);
+    set.insert(u32::MAX - 1..=u32::MAX);
+    assert_eq!(set.last(), Some(u32::MAX));
+    assert_eq!(set.gaps(0..=u32::MAX).collect::<Vec<_>>(), [5..=8]);
+}
+
+#[test]
+fn gaps_inside_bounds() {
+    let set: IntervalSet<usize> = [2..4, 6..8, 10..12].into_iter().collect();
+    let gaps: Vec<_> = set.gaps(0..12).collect();
+    assert_eq!(gaps, [0..=1, 4..=5, 8..=9]);
+    let gaps: Vec<_> = set.gaps(3..=20).collect();
+    assert_eq!(gaps, [4..=5, 8..=9, 12..=20]);
+    assert_eq!(set.gaps(6..8).count(), 0);
+    assert_eq!(set.gaps(5..5).count(), 0);
+}
+
+#[test]
+fn intersect_and_split() {
+    let a: IntervalSet<i64> = [0..10, 20..30].into_iter().collect();
+    let mut b = IntervalSet::new();
+    b.insert(5..25);
+    assert_eq!(runs(&a.intersect(&b)), [(5, 9), (20, 24)]);
+    assert_eq!(runs(&a.union(&b)), [(0, 29)]);
+
+    let (below, rest) = a.split_at(25);
+    assert_eq!(runs(&below), [(0, 9), (20, 24)]);
+    assert_eq!(runs(&rest), [(25, 29)]);
+    let (below, rest) = a.split_at(i64::MIN);
+    assert!(below.is_empty());
+    assert_eq!(rest, a);
+}
+
+#[test]
+fn map_offsets_moves_only_what_is_mapped() {
+    let seeds: IntervalSet<isize> = [79..93, 55..68].into_iter().collect();
+    let soil = seeds.map_offsets([(98..100, -48), (50..98, 2)]);
+    assert_eq!(runs(&soil), [(57, 69), (81, 94)]);
+
+    let set: IntervalSet<u8> = [0..=255].into_iter().collect();
+    let moved = set.map_offsets([(0..=9, -5), (250..=255, 10)]);
+    assert_eq!(runs(&moved), [(0, 4), (10, 249)]);
+}
@@ -0,0 +1,114 @@
+use common::json::Json;
+use helper::Error;
+
+fn parse(s: &str) -> Json {
+    s.parse().unwrap()
+}
+
+fn error(s: &str) -> String {
+    match s.parse::<Json>() {
+        Err(Error::InvalidInput(msg)) => msg,
+        other => panic!("{s:?} gave {other:?}"),
+    }
+}
+
+#[test]
+fn string_escapes() {
+    assert_eq!(
+        parse(r#""a\"b\\c\/d\be\ff\ng\rh\ti""#),
+        Json::String("a\"b\\c/d\u{8}e\u{c}f\ng\rh\ti".into())
+    );
+    assert_eq!(
+        parse(r#""\u0041\u00e9\u4e2d""#),
+        Json::String("Aé中".into())
+    );
+    assert_eq!(parse("\"héllo ☃\""), Json::String("héllo ☃".into()));
+    assert_eq!(parse(r#""""#), Json::String(String::new()));
+}
+
+#[test]
+fn surrogate_pairs() {
+    assert_eq!(parse(r#""\ud83d\ude00""#), Json::String("😀".into()));
+    assert_eq!(parse(r#""\uD834\uDD1E!""#), Json::String("𝄞!".into()));
+    assert_eq!(
+        error(r#""\ud83d""#),
+        "Expected a low surrogate at 7, found '\"'"
+    );
+    assert_eq!(
+        error(r#""\ud83dx""#),
+        "Expected a low surrogate at 7, found 'x'"
+    );
+    assert_eq!(
+        error(r#""\ud83d\u0041""#),
+        "Bad surrogate pair at 2, found 'u'"
+    );
+    assert_eq!(error(r#""\ude00""#), "Bad unicode escape at 2, found 'u'");
+}
+
+#[test]
+fn numbers() {
+    for (s, n) in [
+        ("0", 0.0),
+        ("-0", -0.0),
+        ("42", 42.0),
+        ("-17", -17.0),
+        ("3.25", 3.25),
+        ("-0.5", -0.5),
+        ("1e3", 1000.0),
+        ("1E3", 1000.0),
+        ("2.5e-3", 0.0025),
+        ("-1.5E+2", -150.0),
+    ] {
+        assert_eq!(parse(s), Json::Number(n), "{s}");
+    }
+    assert_eq!(parse("12").as_i64(), Some(12));
+    assert_eq!(parse("1.5").as_i64(), None);
+    assert_eq!(parse("1e300").as_i64(), None);
+}
+
+#[test]
+fn error_positions() {
+    assert_eq!(error(""), "Expected a value at 0, found end of input");
+    assert_eq!(error("[1,]"), "Expected a value at 3, found ']'");
+    assert_eq!(error("[1 2]"), "Expected ',' at 3, found '2'");
+    assert_eq!(error(r#"{"a" 1}"#), "Expected ':' at 5, found '1'");
+    assert_eq!(error("{1:2}"), "Expected '\"' at 1, found '1'");
+    assert_eq!(error("nul"), "Expected null at 0, found 'n'");
+    assert_eq!(error("truex"), "Expected true at 0, found 't'");
+    assert_eq!(error("-"), "Expected a digit at 1, found end of input");
+    assert_eq!(error("1."), "Expected a digit at 2, found end of input");
+    assert_eq!(error("1e+"), "Expected a digit at 3, found end of input");
+    assert_eq!(error("01"), "Unexpected trailing input at 1, found '1'");
+    assert_eq!(
+        error(r#""abc"#),
+        "Unterminated string at 4, found end of input"
+    );
+    assert_eq!(error("\"a\nb\""), "Unterminated string at 2, found '\\n'");
+    assert_eq!(error(r#""\q""#), "Bad escape at 2, found 'q'");
+    assert_eq!(
+        error(r#""\u12g4""#),
+        "Expected four hex digits at 3, found '1'"
+    );
+    assert_eq!(error("[1] 2"), "Unexpected trailing input at 4, found '2'");
+}
+
+#[test]
+fn round_trips_through_display() {
+    for s in [
+        "null",
+        "[true,false,null]",
+        r#"{"a":[1,2,{"b":"c"}],"d":-3.5,"a":0}"#,
+        r#""quote \" backslash \\ newline \n tab \t bell \u0007""#,
+        r#"["😀","中","é"]"#,
+        "[0.1,1e-7,12345678901234567890,-0]",
+        "{}",
+        r#"[[],{},""]"#,
+    ] {
+        let json = parse(s);
+        assert_eq!(parse(&json.to_string()), json, "{s}");
+    }
+    assert_eq!(
+        parse(" { \"a\" : [ 1 , 2.5 ] , \"b\" : \"\\u00e9\" } ").to_string(),
+        r#"{"a":[1,2.5],"b":"é"}"#
+    );
+}
@@ -0,0 +1,91 @@
+use common::{
+    grid::Grid,
+    rotation::{self, Orientation},
+};
+use helper::HashSet;
+
+// A quarter turn which takes x onto y, so clockwise with y pointing down
+const QUARTER: Orientation<2> = Orientation([[0, -1], [1, 0]]);
+const MIRROR: Orientation<2> = Orientation([[-1, 0], [0, 1]]);
+
+#[test]
+fn groups_have_every_orientation_once() {
+    assert_eq!(Orientation::<2>::all().len(), 8);
+    assert_eq!(Orientation::<2>::rotations().len(), 4);
+    assert_eq!(Orientation::<3>::all().len(), 48);
+    assert_eq!(Orientation::<3>::rotations().len(), 24);
+
+    let all = Orientation::<3>::all();
+    assert_eq!(all[0], Orientation::identity());
+    assert_eq!(all.iter().collect::<HashSet<_>>().len(), 48);
+    for a in all.iter() {
+        assert_eq!(a.compose(&a.inverse()), Orientation::identity());
+        for b in all.iter() {
+            let ab = a.compose(b);
+            assert!(all.contains(&ab));
+            assert_eq!(ab.det(), a.det() * b.det());
+            assert_eq!(ab.apply([1, 2, 3]), a.apply(b.apply([1, 2, 3])));
+        }
+    }
+}
+
+#[test]
+fn turning_points() {
+    assert_eq!(QUARTER.apply([1, 0]), [0, 1]);
+    assert_eq!(QUARTER.compose(&QUARTER).apply([3, 4]), [-3, -4]);
+    assert_eq!(MIRROR.det(), -1);
+    assert_eq!(
+        QUARTER.compose(&MIRROR).apply_all(&[[1, 2], [0, -5]]),
+        [[-2, -1], [5, 0]]
+    );
+}
+
+#[test]
+fn turning_squares_and_grids() {
+    let square = [['a', 'b', 'c'], ['d', 'e', 'f'], ['g', 'h', 'i']];
+    assert_eq!(
+        QUARTER.apply_square(&square),
+        [['g', 'd', 'a'], ['h', 'e', 'b'], ['i', 'f', 'c']]
+    );
+    assert_eq!(
+        MIRROR.apply_square(&square),
+        [['c', 'b', 'a'], ['f', 'e', 'd'], ['i', 'h', 'g']]
+    );
+    let even = [[1, 2], [3, 4]];
+    assert_eq!(QUARTER.apply_square(&even), [[3, 1], [4, 2]]);
+
+    let grid: Grid<char> = Grid::parse(["abc", "def"]).unwrap();
+    let turned = grid.turned(&QUARTER);
+    assert_eq!((turned.width(), turned.height()), (2, 3));
+    assert_eq!(turned.to_string(), "da\neb\nfc\n");
+    for orientation in Orientation::all() {
+        assert_eq!(
+            grid.turned(&orientation).turned(&orientation.inverse()),
+            grid
+        );
+    }
+}
+
+#[test]
+fn aligning_point_clouds() {
+    let points: Vec<[isize; 3]> = (0..20)
+        .map(|i| [i * 7 % 13, i * i % 17 - 8, i * 3 - 30])
+        .collect();
+    let rotations = Orientation::rotations();
+    let turn = rotations[17];
+    let target: HashSet<[isize; 3]> = turn
+        .apply_all(&points[..15])
+        .into_iter()
+        .map(|p| [p[0] + 100, p[1] - 40, p[2] + 3])
+        .chain([[0, 0, 0], [1, 1, 1]])
+        .collect();
+
+    let alignment = rotation::align(&rotations, &points, &target, 12).unwrap();
+    assert_eq!(alignment.orientation, turn);
+    assert_eq!(alignment.offset, [100, -40, 3]);
+    assert_eq!(alignment.apply(points[3]), {
+        let p = turn.apply(points[3]);
+        [p[0] + 100, p[1] - 40, p[2] + 3]
+    });
+    assert!(rotation::align(&rotations, &points, &target, 16).is_none());
+}
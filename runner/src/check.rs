@@ -0,0 +1,211 @@
+use crate::limit::{self, Ran};
+use helper::{Error, NewRunner};
+use std::{collections::BTreeMap, time::Duration};
+use toml::{Table, Value};
+
+const USAGE: &str =
+    "Usage: runner --check [--record] [--strict] [--threads <n>] [--timeout <s>]
+                     [--answers <dir>] [--input <dir>] [year [day]]
+
+Runs every registered day and compares each part against <answers>/<year>.toml, which holds
+one table per day keyed by part:
+
+    [1]
+    1 = 1233
+    2 = \"ABCDEFGH\"
+
+Inputs are read from <input>/<year>/<day>.txt with the day zero padded to two digits.
+Any wrong answer, error, panic or timeout makes the check exit non-zero.  A part with no
+recorded answer or no input is reported as MISSING, which only fails the check with --strict.
+
+--record writes the answer of every part which has none yet into <answers>/<year>.toml, so a
+run on inputs whose answers have been accepted seeds the file for later checks.  Parts which
+already have an answer are compared as usual and never overwritten.  --threads limits the MD5
+searches, which otherwise use AOC_THREADS or every core.  A part still running after --timeout seconds is
+cancelled and reported as timed out, with 0 meaning no limit.  Defaults are 60 seconds, answers/
+and input/.";
+
+type Register = fn(&mut BTreeMap<(usize, usize), (u8, NewRunner)>);
+
+#[derive(Default)]
+struct Tally {
+    pass: usize,
+    fail: usize,
+    missing: usize,
+    error: usize,
+    unsolved: usize,
+    timed_out: usize,
+    skipped: usize,
+}
+
+fn usage() -> Error {
+    Error::Runner(USAGE.into())
+}
+
+fn load_answers(path: &str) -> Result<Table, Error> {
+    match std::fs::read_to_string(path) {
+        Ok(s) => s
+            .parse()
+            .map_err(|e| Error::InvalidInput(format!("{path}: {e}"))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Table::new()),
+        Err(e) => Err(Error::Runner(format!("{path}: {e}"))),
+    }
+}
+
+fn answer_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+pub fn main(register: Register, args: &[String]) -> Result<(), Error> {
+    let mut record = false;
+    let mut strict = false;
+    let mut answers_dir = "answers".to_string();
+    let mut input_dir = "input".to_string();
+    let mut timeout = 60;
+    let mut filter = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => record = true,
+            "--strict" => strict = true,
+            "--threads" => common::md5::set_threads(
+                args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            ),
+            "--timeout" => timeout = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            "--answers" => answers_dir = args.next().ok_or_else(usage)?.clone(),
+            "--input" => input_dir = args.next().ok_or_else(usage)?.clone(),
+            _ => filter.push(arg.parse::<usize>().map_err(|_| usage())?),
+        }
+    }
+    if filter.len() > 2 {
+        return Err(usage());
+    }
+
+    let timeout = (timeout > 0).then(|| Duration::from_secs(timeout));
+
+    let mut runners = BTreeMap::new();
+    register(&mut runners);
+    limit::catch_panics();
+
+    let mut tally = Tally::default();
+    let mut years: BTreeMap<usize, Vec<(usize, u8, NewRunner)>> = BTreeMap::new();
+    for (&(year, day), &(parts, new)) in runners.iter() {
+        if filter.first().is_some_and(|y| *y != year) || filter.get(1).is_some_and(|d| *d != day) {
+            continue;
+        }
+        years.entry(year).or_default().push((day, parts, new));
+    }
+
+    for (year, days) in years {
+        let answers_path = format!("{answers_dir}/{year}.toml");
+        let mut answers = load_answers(&answers_path)?;
+        let mut recorded = 0;
+
+        for (day, parts, new) in days {
+            let input_path = format!("{input_dir}/{year}/{day:02}.txt");
+            let Ok(input) = std::fs::read(&input_path) else {
+                println!("{year} day {day:2}: NO INPUT ({input_path})");
+                tally.missing += parts as usize;
+                continue;
+            };
+
+            for part in 1..=parts {
+                let label = format!("{year} day {day:2} part {part}");
+                let output = match limit::run(new, input.clone(), part, timeout) {
+                    Ran::Answer(output) => output,
+                    Ran::Skipped => {
+                        println!("{label}: SKIPPED");
+                        tally.skipped += 1;
+                        continue;
+                    }
+                    Ran::Unsolved => {
+                        println!("{label}: UNSOLVED");
+                        tally.unsolved += 1;
+                        continue;
+                    }
+                    Ran::Failed(e) => {
+                        println!("{label}: ERROR {e}");
+                        tally.error += 1;
+                        continue;
+                    }
+                    Ran::Panicked(site, message) => {
+                        println!("{label}: PANIC at {site}: {message}");
+                        tally.error += 1;
+                        continue;
+                    }
+                    Ran::TimedOut(stopped) => {
+                        let still = if stopped { "" } else { ", still running" };
+                        println!("{label}: TIMED OUT{still}");
+                        tally.timed_out += 1;
+                        continue;
+                    }
+                };
+
+                let expected = answers
+                    .get(&day.to_string())
+                    .and_then(|day| day.get(part.to_string()))
+                    .map(answer_string);
+                match expected {
+                    Some(expected) if expected == output => {
+                        println!("{label}: PASS");
+                        tally.pass += 1;
+                    }
+                    Some(expected) => {
+                        println!("{label}: FAIL expected {expected:?}, got {output:?}");
+                        tally.fail += 1;
+                    }
+                    None if record => {
+                        println!("{label}: RECORDED {output:?}");
+                        let value = match output.parse::<i64>() {
+                            Ok(v) => Value::Integer(v),
+                            Err(_) => Value::String(output),
+                        };
+                        if let Value::Table(day) = answers
+                            .entry(day.to_string())
+                            .or_insert_with(|| Value::Table(Table::new()))
+                        {
+                            day.insert(part.to_string(), value);
+                            recorded += 1;
+                        }
+                    }
+                    None => {
+                        println!("{label}: MISSING answer, got {output:?}");
+                        tally.missing += 1;
+                    }
+                }
+            }
+        }
+
+        if recorded > 0 {
+            std::fs::create_dir_all(&answers_dir)
+                .map_err(|e| Error::Runner(format!("{answers_dir}: {e}")))?;
+            std::fs::write(&answers_path, answers.to_string())
+                .map_err(|e| Error::Runner(format!("{answers_path}: {e}")))?;
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} errors, {} unsolved, {} timed out, {} skipped, {} missing",
+        tally.pass,
+        tally.fail,
+        tally.error,
+        tally.unsolved,
+        tally.timed_out,
+        tally.skipped,
+        tally.missing
+    );
+    let mut failed = tally.fail + tally.error + tally.unsolved + tally.timed_out;
+    if strict {
+        failed += tally.missing;
+    }
+    if failed > 0 {
+        Err(Error::Runner(format!(
+            "{failed} parts failed the answer check"
+        )))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use common::dot::{NewDump, RegisterDumps};
+use helper::Error;
+use std::collections::BTreeMap;
+
+const USAGE: &str = "Usage: runner --dot [--input <dir>] <year> <day>
+
+Parses <input>/<year>/<day>.txt and writes the graph it describes to <input>/<year>/<day>.dot,
+with the day zero padded to two digits.  Render it with e.g. `dot -Tsvg`.  The default input
+directory is input/.";
+
+fn usage() -> Error {
+    Error::Runner(USAGE.into())
+}
+
+pub fn main(register: RegisterDumps, args: &[String]) -> Result<(), Error> {
+    let mut input_dir = "input".to_string();
+    let mut day = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input_dir = args.next().ok_or_else(usage)?.clone(),
+            _ => day.push(arg.parse::<usize>().map_err(|_| usage())?),
+        }
+    }
+    let [year, day] = day[..] else {
+        return Err(usage());
+    };
+
+    let mut dumps: BTreeMap<(usize, usize), NewDump> = BTreeMap::new();
+    register(&mut dumps);
+    let Some(dump) = dumps.get(&(year, day)) else {
+        let days: Vec<String> = dumps
+            .keys()
+            .map(|(year, day)| format!("{year} day {day}"))
+            .collect();
+        return Err(Error::Runner(format!(
+            "{year} day {day} has no graph to dump.  Days which do: {}",
+            days.join(", ")
+        )));
+    };
+
+    let input_path = format!("{input_dir}/{year}/{day:02}.txt");
+    let input =
+        std::fs::read(&input_path).map_err(|e| Error::Runner(format!("{input_path}: {e}")))?;
+    let dot_path = format!("{input_dir}/{year}/{day:02}.dot");
+    std::fs::write(&dot_path, dump(&input)?)
+        .map_err(|e| Error::Runner(format!("{dot_path}: {e}")))?;
+    println!("Wrote {dot_path}");
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use helper::{Error, NewRunner};
+use std::collections::BTreeMap;
+
+const USAGE: &str =
+    "Usage: runner --frames [--input <dir>] [--out <dir>] [--every <n>] [--scale <n>]
+                     <year> <day> [part]
+
+Runs a day on <input>/<year>/<day>.txt with frame recording on, writing what the day draws to
+<out>/<year>_<day>_<part>/frame_00000.ppm and onwards, with the day zero padded to two digits.
+--every keeps only one frame in every n and --scale draws each cell as an n by n square.  Both
+parts run unless one is given.  Defaults are input/, frames/, 1 and 4.  Turn the frames into an
+animation with e.g. `ffmpeg -i frame_%05d.ppm out.gif`.";
+
+type Register = fn(&mut BTreeMap<(usize, usize), (u8, NewRunner)>);
+
+fn usage() -> Error {
+    Error::Runner(USAGE.into())
+}
+
+pub fn main(register: Register, args: &[String]) -> Result<(), Error> {
+    let mut input_dir = "input".to_string();
+    let mut out_dir = "frames".to_string();
+    let mut every = 1;
+    let mut scale = 4;
+    let mut day = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input_dir = args.next().ok_or_else(usage)?.clone(),
+            "--out" => out_dir = args.next().ok_or_else(usage)?.clone(),
+            "--every" => every = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            "--scale" => scale = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            _ => day.push(arg.parse::<usize>().map_err(|_| usage())?),
+        }
+    }
+    let (year, day, part) = match day[..] {
+        [year, day] => (year, day, None),
+        [year, day, part] => (year, day, Some(part as u8)),
+        _ => return Err(usage()),
+    };
+
+    let mut runners = BTreeMap::new();
+    register(&mut runners);
+    let Some(&(parts, new)) = runners.get(&(year, day)) else {
+        return Err(Error::Runner(format!("{year} day {day} is not registered")));
+    };
+
+    let input_path = format!("{input_dir}/{year}/{day:02}.txt");
+    let input =
+        std::fs::read(&input_path).map_err(|e| Error::Runner(format!("{input_path}: {e}")))?;
+    for part in (1..=parts).filter(|p| part.is_none() || part == Some(*p)) {
+        let dir = format!("{out_dir}/{year}_{day:02}_{part}");
+        common::frames::start(&dir, every, scale)?;
+        let mut runner = new();
+        let output = runner
+            .parse(&input, part)
+            .and_then(|_| runner.run_part(part));
+        let written = common::frames::finish()?;
+        match output {
+            Ok(output) => println!("{year} day {day} part {part}: {output}"),
+            Err(Error::Skipped) => continue,
+            Err(e) => return Err(e),
+        }
+        println!("Wrote {written} frames to {dir}");
+    }
+    Ok(())
+}
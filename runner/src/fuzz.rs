@@ -0,0 +1,239 @@
+use crate::limit::{self, Ran};
+use helper::{Error, NewRunner};
+use std::{collections::BTreeMap, time::Duration};
+
+const USAGE: &str =
+    "Usage: runner --fuzz [--runs <n>] [--seed <n>] [--timeout <ms>] [--input <dir>]
+                   [--samples <dir>] [--artifacts <dir>] [year [day [part]]]
+
+Feeds mutated copies of each day's stored inputs to its parse and run_part, and reports every
+run which panicked or took longer than the timeout.  Inputs are seeded from
+<input>/<year>/<day>.txt and the samples in <samples>/aoc_<year>/samples/<day>.txt, with the day
+zero padded to two digits, and days with neither are skipped.  Runs returning any Error count
+as handled.  With --artifacts the first input reaching each panic is written to
+<artifacts>/<year>_<day>_<part>_<n>.txt for replaying.  Timed out runs are cancelled, but a part
+stops being fuzzed after its first time out as a day which does not poll for cancellation cannot
+be stopped.  Defaults are 100 runs, seed 1, 1000 ms, input/ and the current directory.
+
+Failed allocations and stack overflows abort the whole process rather than panicking, which
+shows up as the report ending at that day.";
+
+type Register = fn(&mut BTreeMap<(usize, usize), (u8, NewRunner)>);
+
+fn usage() -> Error {
+    Error::Runner(USAGE.into())
+}
+
+// splitmix64, so a seed always produces the same runs
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+const BYTES: &[u8] = b"0123456789-+ ,.:;=#()[]<>xyzAZ\n\t\0\xff";
+const NUMBERS: &[&str] = &["", "0", "-1", "99999", "18446744073709551616"];
+
+// The start and end of the line around `at`
+fn line_at(input: &[u8], at: usize) -> (usize, usize) {
+    let start = input[..at]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let end = input[at..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(input.len(), |i| at + i + 1);
+    (start, end)
+}
+
+fn mutate(rng: &mut Rng, seed: &[u8]) -> Vec<u8> {
+    let mut input = seed.to_vec();
+    if input.is_empty() {
+        return vec![*rng.pick(BYTES)];
+    }
+    for _ in 0..1 + rng.below(3) {
+        if input.is_empty() {
+            break;
+        }
+        let at = rng.below(input.len());
+        match rng.below(7) {
+            0 => input.truncate(at),
+            1 => {
+                let end = (at + 1 + rng.below(16)).min(input.len());
+                input.drain(at..end);
+            }
+            2 => input[at] = *rng.pick(BYTES),
+            3 => input.insert(at, *rng.pick(BYTES)),
+            4 => {
+                let (start, end) = line_at(&input, at);
+                input.drain(start..end);
+            }
+            5 => {
+                let (start, end) = line_at(&input, at);
+                let line = input[start..end].to_vec();
+                input.splice(start..start, line);
+            }
+            _ => {
+                // Swap the number under `at` for an awkward one, or insert one if there is none
+                let mut start = at;
+                while start > 0 && input[start - 1].is_ascii_digit() {
+                    start -= 1;
+                }
+                let mut end = at;
+                while end < input.len() && input[end].is_ascii_digit() {
+                    end += 1;
+                }
+                input.splice(start..end, rng.pick(NUMBERS).bytes());
+            }
+        }
+    }
+    input
+}
+
+fn seeds(input_dir: &str, samples_dir: &str, year: usize, day: usize, part: u8) -> Vec<Vec<u8>> {
+    let mut seeds = Vec::new();
+    if let Ok(input) = std::fs::read(format!("{input_dir}/{year}/{day:02}.txt")) {
+        seeds.push(input);
+    }
+    let path = format!("{samples_dir}/aoc_{year}/samples/{day:02}.txt");
+    if let Ok(samples) = std::fs::read_to_string(path) {
+        for sample in common::samples::parse(&samples).unwrap_or_default() {
+            if sample.part == part {
+                seeds.push(sample.input.into_bytes());
+            }
+        }
+    }
+    seeds
+}
+
+#[derive(Default)]
+struct Tally {
+    answered: usize,
+    handled: usize,
+    panicked: usize,
+    timed_out: usize,
+}
+
+pub fn main(register: Register, args: &[String]) -> Result<(), Error> {
+    let mut runs = 100;
+    let mut seed = 1;
+    let mut timeout = 1000;
+    let mut input_dir = "input".to_string();
+    let mut samples_dir = ".".to_string();
+    let mut artifacts_dir = None;
+    let mut filter = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => runs = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            "--seed" => seed = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            "--timeout" => timeout = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+            "--input" => input_dir = args.next().ok_or_else(usage)?.clone(),
+            "--samples" => samples_dir = args.next().ok_or_else(usage)?.clone(),
+            "--artifacts" => artifacts_dir = Some(args.next().ok_or_else(usage)?.clone()),
+            _ => filter.push(arg.parse::<usize>().map_err(|_| usage())?),
+        }
+    }
+    if filter.len() > 3 {
+        return Err(usage());
+    }
+    if let Some(dir) = &artifacts_dir {
+        std::fs::create_dir_all(dir).map_err(|e| Error::Runner(format!("{dir}: {e}")))?;
+    }
+    let timeout = Some(Duration::from_millis(timeout));
+
+    let mut runners = BTreeMap::new();
+    register(&mut runners);
+
+    limit::catch_panics();
+    let mut rng = Rng(seed);
+    let mut total = Tally::default();
+    let mut failing = 0;
+    for (&(year, day), &(parts, new)) in runners.iter() {
+        if filter.first().is_some_and(|y| *y != year) || filter.get(1).is_some_and(|d| *d != day) {
+            continue;
+        }
+        for part in 1..=parts {
+            if filter.get(2).is_some_and(|p| *p != part as usize) {
+                continue;
+            }
+            let seeds = seeds(&input_dir, &samples_dir, year, day, part);
+            if seeds.is_empty() {
+                continue;
+            }
+
+            let mut tally = Tally::default();
+            // Distinct panic sites in the order found, with the first message and how often each
+            // was hit
+            let mut sites: Vec<(String, String, usize)> = Vec::new();
+            for _ in 0..runs {
+                let from = rng.below(seeds.len());
+                let input = mutate(&mut rng, &seeds[from]);
+                match limit::run(new, input.clone(), part, timeout) {
+                    Ran::Answer(_) => tally.answered += 1,
+                    Ran::Skipped | Ran::Unsolved | Ran::Failed(_) => tally.handled += 1,
+                    Ran::Panicked(site, message) => {
+                        tally.panicked += 1;
+                        if let Some(seen) = sites.iter_mut().find(|(s, _, _)| *s == site) {
+                            seen.2 += 1;
+                            continue;
+                        }
+                        if let Some(dir) = &artifacts_dir {
+                            let path =
+                                format!("{dir}/{year}_{day:02}_{part}_{}.txt", sites.len() + 1);
+                            std::fs::write(&path, &input)
+                                .map_err(|e| Error::Runner(format!("{path}: {e}")))?;
+                        }
+                        sites.push((site, message, 1));
+                    }
+                    Ran::TimedOut(_) => {
+                        tally.timed_out += 1;
+                        break;
+                    }
+                }
+            }
+
+            println!(
+                "{year} day {day:2} part {part}: {} answered, {} handled, {} panicked, {} timed out",
+                tally.answered, tally.handled, tally.panicked, tally.timed_out
+            );
+            for (site, message, count) in sites.iter() {
+                println!("    {count:4} x {site}: {message}");
+            }
+            if tally.panicked + tally.timed_out > 0 {
+                failing += 1;
+            }
+            total.answered += tally.answered;
+            total.handled += tally.handled;
+            total.panicked += tally.panicked;
+            total.timed_out += tally.timed_out;
+        }
+    }
+
+    println!(
+        "{} answered, {} handled, {} panicked, {} timed out",
+        total.answered, total.handled, total.panicked, total.timed_out
+    );
+    if failing > 0 {
+        Err(Error::Runner(format!(
+            "{failing} parts panicked or timed out on malformed input"
+        )))
+    } else {
+        Ok(())
+    }
+}
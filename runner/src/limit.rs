@@ -0,0 +1,244 @@
+use common::cancel::{self, Token};
+use helper::{Error, NewRunner, RunOutput, Runner};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    panic::AssertUnwindSafe,
+    sync::{mpsc, OnceLock},
+    time::Duration,
+};
+
+// Days run on their own thread, which gets enough stack for the deeply recursive ones
+const STACK: usize = 256 << 20;
+
+// How long a cancelled day gets to notice before it is left running
+const GRACE: Duration = Duration::from_secs(1);
+
+// How one part of a day ended
+pub enum Ran {
+    Answer(String),
+    Skipped,
+    Unsolved,
+    // Any other error, in its debug form
+    Failed(String),
+    // Where it panicked and the first line of the message
+    Panicked(String, String),
+    // Out of time, and whether the day stopped once cancelled
+    TimedOut(bool),
+}
+
+thread_local! {
+    // Set on the threads days run on, whose panics are caught and reported with the part
+    static DAY_THREAD: Cell<bool> = const { Cell::new(false) };
+    static PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+// Keeps panic messages from day threads off stderr, leaving them for whoever reports the run
+// which panicked.  Panics anywhere else still go to the hook which was there before.
+pub fn catch_panics() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !DAY_THREAD.with(Cell::get) {
+            return previous(info);
+        }
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let message = message.lines().next().unwrap_or_default().to_string();
+        let site = match info.location() {
+            Some(location) => format!("{}:{}", location.file(), location.line()),
+            None => "unknown".to_string(),
+        };
+        PANIC.with(|panic| *panic.borrow_mut() = Some((site, message)));
+    }));
+}
+
+// Something for a day's runner to do, giving the answer if there is one
+type Job = Box<dyn FnOnce(&mut dyn Runner) -> Result<String, Error> + Send>;
+
+// A day's runner on a thread of its own, which is handed jobs one at a time, each with its own
+// cancellation token.  Once a job's limit passes its token is set, and if it has not stopped
+// shortly after the worker is left behind.
+struct Worker {
+    jobs: mpsc::Sender<(Token, Job)>,
+    ran: mpsc::Receiver<Ran>,
+    stuck: bool,
+}
+
+impl Worker {
+    fn spawn(new: NewRunner) -> Result<Self, String> {
+        let (jobs, job_rx) = mpsc::channel::<(Token, Job)>();
+        let (ran_tx, ran) = mpsc::channel();
+        std::thread::Builder::new()
+            .stack_size(STACK)
+            .spawn(move || {
+                DAY_THREAD.with(|day| day.set(true));
+                let mut runner = new();
+                for (token, job) in job_rx {
+                    cancel::install(token);
+                    let result =
+                        std::panic::catch_unwind(AssertUnwindSafe(|| job(runner.as_mut())));
+                    let ran = match result {
+                        Ok(Ok(answer)) => Ran::Answer(answer),
+                        Ok(Err(Error::Skipped)) => Ran::Skipped,
+                        Ok(Err(Error::Unsolved)) => Ran::Unsolved,
+                        Ok(Err(e)) => Ran::Failed(format!("{e:?}")),
+                        Err(_) => {
+                            let (site, message) = PANIC
+                                .with(|panic| panic.borrow_mut().take())
+                                .unwrap_or_default();
+                            Ran::Panicked(site, message)
+                        }
+                    };
+                    if ran_tx.send(ran).is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| format!("Cannot start a thread: {e}"))?;
+        Ok(Self {
+            jobs,
+            ran,
+            stuck: false,
+        })
+    }
+
+    fn run(&mut self, job: Job, limit: Option<Duration>) -> Ran {
+        let gone = || Ran::Failed("Day thread went away".into());
+        if self.stuck {
+            return Ran::Failed("Day is still running a part which timed out".into());
+        }
+        let token = Token::new();
+        if self.jobs.send((token.clone(), job)).is_err() {
+            return gone();
+        }
+        let Some(limit) = limit else {
+            return self.ran.recv().unwrap_or_else(|_| gone());
+        };
+        match self.ran.recv_timeout(limit) {
+            Ok(ran) => ran,
+            Err(mpsc::RecvTimeoutError::Disconnected) => gone(),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                token.cancel();
+                let stopped = self.ran.recv_timeout(GRACE).is_ok();
+                self.stuck = !stopped;
+                Ran::TimedOut(stopped)
+            }
+        }
+    }
+}
+
+// Parses `input` and runs one part of a day on a thread of its own, with `limit` covering both
+pub fn run(new: NewRunner, input: Vec<u8>, part: u8, limit: Option<Duration>) -> Ran {
+    match Worker::spawn(new) {
+        Ok(mut worker) => worker.run(
+            Box::new(move |runner| {
+                runner.parse(&input, part)?;
+                Ok(runner.run_part(part)?.to_string())
+            }),
+            limit,
+        ),
+        Err(e) => Ran::Failed(e),
+    }
+}
+
+type Days = BTreeMap<(usize, usize), (u8, NewRunner)>;
+
+// What `limited` days stand in for, set once by `limit_days`
+static DAYS: OnceLock<(Days, Option<Duration>)> = OnceLock::new();
+
+// Parses and runs each part of a day on a `Worker`, so days run by the helper's runner get the
+// same time limit and cancellation as --check.  The worker is started up front and keeps the
+// parsed day, so the helper's parse and part timings each cover only their own job.
+struct Limited {
+    limit: Option<Duration>,
+    worker: Result<Worker, String>,
+}
+
+// What the helper's runner is told about a job
+fn answer(ran: Ran) -> Result<String, Error> {
+    match ran {
+        Ran::Answer(answer) => Ok(answer),
+        Ran::Skipped => Err(Error::Skipped),
+        Ran::Unsolved => Err(Error::Unsolved),
+        Ran::Failed(e) => Err(Error::Runner(e)),
+        Ran::Panicked(site, message) => Err(Error::Runner(format!("PANIC at {site}: {message}"))),
+        Ran::TimedOut(stopped) => {
+            let still = if stopped { "" } else { ", still running" };
+            Err(Error::Runner(format!("TIMED OUT{still}")))
+        }
+    }
+}
+
+impl Limited {
+    fn run(&mut self, job: Job) -> Result<String, Error> {
+        let worker = self.worker.as_mut().map_err(|e| Error::Runner(e.clone()))?;
+        answer(worker.run(job, self.limit))
+    }
+}
+
+impl Runner for Limited {
+    fn parse(&mut self, file: &[u8], part: u8) -> Result<(), Error> {
+        let input = file.to_vec();
+        self.run(Box::new(move |runner| {
+            runner.parse(&input, part).map(|_| String::new())
+        }))?;
+        Ok(())
+    }
+
+    fn run_part(&mut self, part: u8) -> Result<RunOutput, Error> {
+        let answer = self.run(Box::new(move |runner| {
+            Ok(runner.run_part(part)?.to_string())
+        }))?;
+        Ok(answer.into())
+    }
+}
+
+fn limited<const YEAR: usize, const DAY: usize>() -> Box<dyn Runner> {
+    let (days, limit) = DAYS.get().expect("limited days are registered");
+    Box::new(Limited {
+        limit: *limit,
+        worker: Worker::spawn(days[&(YEAR, DAY)].1),
+    })
+}
+
+// A `limited` day for every year given, and each of its 25 days
+macro_rules! limited {
+    ($year:expr, $day:expr; $($y:literal)*) => {
+        match $year {
+            $($y => limited!(@days $y, $day),)*
+            _ => None,
+        }
+    };
+    (@days $y:literal, $day:expr) => {
+        limited!(@day $y, $day; 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25)
+    };
+    (@day $y:literal, $day:expr; $($d:literal)*) => {
+        match $day {
+            $($d => Some(limited::<$y, $d> as NewRunner),)*
+            _ => None,
+        }
+    };
+}
+
+// Wraps every day from `register` so each part runs through `run` with `limit`, for
+// `register_limited` to hand out
+pub fn limit_days(register: fn(&mut Days), limit: Option<Duration>) {
+    DAYS.get_or_init(|| {
+        let mut days = BTreeMap::new();
+        register(&mut days);
+        (days, limit)
+    });
+}
+
+pub fn register_limited(runners: &mut Days) {
+    let (days, _) = DAYS.get().expect("limit_days is called first");
+    for (&(year, day), &(parts, new)) in days.iter() {
+        let new =
+            limited!(year, day; 2015 2016 2017 2018 2019 2020 2021 2022 2023 2024).unwrap_or(new);
+        runners.insert((year, day), (parts, new));
+    }
+}
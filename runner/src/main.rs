@@ -1,5 +1,11 @@
 use helper::{Error, NewRunner};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
+
+mod check;
+mod dot;
+mod frames;
+mod fuzz;
+mod limit;
 
 const README_HEADER: &str = "[Advent of Code](https://adventofcode.com/)
 Michael Conrad
@@ -20,6 +26,17 @@ fn register(runners: &mut BTreeMap<(usize, usize), (u8, NewRunner)>) {
     aoc_2024::register(runners);
 }
 
+fn register_dumps(dumps: &mut BTreeMap<(usize, usize), common::dot::NewDump>) {
+    aoc_2015::register_dumps(dumps);
+    aoc_2017::register_dumps(dumps);
+    aoc_2018::register_dumps(dumps);
+    aoc_2019::register_dumps(dumps);
+    aoc_2021::register_dumps(dumps);
+    aoc_2022::register_dumps(dumps);
+    aoc_2023::register_dumps(dumps);
+    aoc_2024::register_dumps(dumps);
+}
+
 fn today(year: usize, month: usize, day: usize) -> (usize, usize) {
     if month == 12 {
         (year, day.min(25))
@@ -28,8 +45,50 @@ fn today(year: usize, month: usize, day: usize) -> (usize, usize) {
     }
 }
 
+// Worker threads for the parallel MD5 searches.  The helper runner owns the command line, so
+// this is set from the environment; AOC_THREADS=1 reproduces the single threaded README times.
+fn set_threads() -> Result<(), Error> {
+    if let Ok(threads) = std::env::var("AOC_THREADS") {
+        let threads = threads
+            .parse()
+            .map_err(|_| Error::Runner(format!("Bad AOC_THREADS {threads:?}")))?;
+        common::md5::set_threads(threads);
+    }
+    Ok(())
+}
+
+// How long each part may run before it is cancelled and reported as timed out, in seconds.  As
+// with AOC_THREADS this comes from the environment, AOC_TIMEOUT=0 meaning no limit, and the
+// default matches --check.
+fn timeout() -> Result<Option<Duration>, Error> {
+    let timeout = match std::env::var("AOC_TIMEOUT") {
+        Ok(timeout) => timeout
+            .parse()
+            .map_err(|_| Error::Runner(format!("Bad AOC_TIMEOUT {timeout:?}")))?,
+        Err(_) => 60,
+    };
+    Ok((timeout > 0).then(|| Duration::from_secs(timeout)))
+}
+
 fn main() -> Result<(), Error> {
-    let mut config = helper::runner::Config::new(register, today);
+    set_threads()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "--check") {
+        return check::main(register, &args[1..]);
+    }
+    if args.first().is_some_and(|arg| arg == "--dot") {
+        return dot::main(register_dumps, &args[1..]);
+    }
+    if args.first().is_some_and(|arg| arg == "--frames") {
+        return frames::main(register, &args[1..]);
+    }
+    if args.first().is_some_and(|arg| arg == "--fuzz") {
+        return fuzz::main(register, &args[1..]);
+    }
+
+    limit::limit_days(register, timeout()?);
+    limit::catch_panics();
+    let mut config = helper::runner::Config::new(limit::register_limited, today);
     config.download_input(true);
     config.readme_header(README_HEADER);
     helper::runner::main::<_, _, 2>(config)